@@ -1,10 +1,9 @@
 use anyhow::Result;
 use softbuffer::Surface;
 use std::{
-    cell::RefCell,
     rc::Rc,
-    sync::Arc,
-    task::{Context, Poll, Waker},
+    task::Poll,
+    thread,
     time::{Duration, Instant},
 };
 use winit::{
@@ -16,10 +15,10 @@ use winit::{
 };
 
 use crate::{
+    engine::GameEngine,
     graphics::{SCREEN_HEIGHT, SCREEN_WIDTH},
-    input::InputHelper,
     screen::{get_screen_state, screen, set_screen_state},
-    task::Signal,
+    task::ClockMode,
 };
 
 pub struct Application {
@@ -27,10 +26,9 @@ pub struct Application {
     event_loop: EventLoop<()>,
     surface: Surface<Rc<Window>, Rc<Window>>,
     scale: u32,
-    input: Rc<RefCell<InputHelper>>,
+    fullscreen: bool,
 }
 
-const DEFAULT_DELAY: Duration = Duration::from_millis(1);
 const SCREEN_REDRAW: Duration = Duration::from_millis(1000 / 30);
 
 impl Application {
@@ -65,31 +63,40 @@ impl Application {
             event_loop,
             surface,
             scale,
-            input: Rc::new(RefCell::new(InputHelper::new())),
+            fullscreen: false,
         })
     }
 
-    pub fn run(
-        mut self,
-        mut step: impl FnMut(&mut Context<'static>, &Signal) -> Result<Poll<()>> + 'static,
-    ) -> Result<()> {
-        let signal = Arc::new(Signal::new());
-        let waker = Waker::from(Arc::clone(&signal));
-        let mut ctx = Context::from_waker(Box::leak(Box::new(waker)));
+    /// Scales the window unless it wouldn't fit the monitor.
+    pub fn with_scale(mut self, scale: u32) -> Self {
+        if resize_window(&self.window, &mut self.surface, scale) {
+            self.scale = scale;
+        }
 
+        self
+    }
+
+    pub fn with_fullscreen(mut self) -> Self {
+        self.fullscreen = true;
+        self.window
+            .set_fullscreen(Some(Fullscreen::Borderless(None)));
+
+        self
+    }
+
+    pub fn run(mut self, mut game: GameEngine) -> Result<()> {
         let mut last_redraw = Instant::now();
         let mut modifiers = ModifiersState::default();
-        let mut fullscreen = false;
 
         self.event_loop.run(move |event, elwt| {
             match &event {
-                Event::NewEvents(_) => {
-                    self.input.as_ref().borrow_mut().clear();
-                }
                 Event::WindowEvent { event, .. } => match event {
                     WindowEvent::ModifiersChanged(new) => {
                         modifiers = new.state();
                     }
+                    WindowEvent::Focused(false) => {
+                        game.release_all();
+                    }
                     WindowEvent::Resized(size) => {
                         self.surface
                             .resize(
@@ -115,6 +122,7 @@ impl Application {
 
                         buf.present().unwrap();
 
+                        game.frame_presented();
                         set_screen_state();
                         last_redraw = Instant::now();
                     }
@@ -127,43 +135,28 @@ impl Application {
                             },
                         ..
                     } if modifiers.alt_key() => match c.as_str() {
-                        c @ ("=" | "-") if !fullscreen => {
+                        c @ ("=" | "-") if !self.fullscreen => {
                             let scale = match (c, self.scale) {
                                 ("=", s @ ..=3) => s + 1,
                                 ("-", s @ 2..) => s - 1,
                                 (_, _) => return,
                             };
 
-                            let Some(mon) = self.window.current_monitor() else {
-                                return;
-                            };
-
-                            let size =
-                                PhysicalSize::new(SCREEN_WIDTH * scale, SCREEN_HEIGHT * scale);
-
-                            if size < mon.size() {
-                                if let Some(size) = self.window.request_inner_size(size) {
-                                    self.surface
-                                        .resize(
-                                            size.width.try_into().unwrap(),
-                                            size.height.try_into().unwrap(),
-                                        )
-                                        .unwrap();
-
-                                    self.scale = scale;
-                                }
+                            if resize_window(&self.window, &mut self.surface, scale) {
+                                self.scale = scale;
                             }
                         }
                         "f" => {
-                            fullscreen = !fullscreen;
+                            self.fullscreen = !self.fullscreen;
 
-                            self.window
-                                .set_fullscreen(fullscreen.then_some(Fullscreen::Borderless(None)));
+                            self.window.set_fullscreen(
+                                self.fullscreen.then_some(Fullscreen::Borderless(None)),
+                            );
                         }
                         _ => (),
                     },
                     WindowEvent::KeyboardInput { event, .. } => {
-                        self.input.borrow_mut().handle(event.clone());
+                        game.handle_event(event.clone().into());
                     }
                     WindowEvent::CloseRequested => {
                         elwt.exit();
@@ -171,15 +164,34 @@ impl Application {
                     _ => {}
                 },
                 Event::AboutToWait => {
-                    let result = step(&mut ctx, &signal);
+                    // any event ends the wait, but a fixed step clock moves by a step on every
+                    // step however early it comes, so the recorded runs wait for the time of
+                    // their next step to keep the pace of the wall clock
+                    let early = game.clock().mode() != ClockMode::RealTime
+                        && game.next_wakeup().is_some_and(|x| x > Instant::now());
+
+                    let result = match early {
+                        true => Ok(Poll::Pending),
+                        false => game.step(),
+                    };
 
                     match result {
                         Ok(Poll::Pending) => {
-                            if get_screen_state() && last_redraw.elapsed() >= SCREEN_REDRAW {
-                                self.window.request_redraw();
-                            } else {
-                                elwt.set_control_flow(ControlFlow::wait_duration(DEFAULT_DELAY));
+                            let next_redraw = last_redraw + SCREEN_REDRAW;
+                            let mut wakeup = game.next_wakeup();
+
+                            if get_screen_state() {
+                                if Instant::now() >= next_redraw {
+                                    self.window.request_redraw();
+                                }
+
+                                wakeup = Some(wakeup.map_or(next_redraw, |x| x.min(next_redraw)));
                             }
+
+                            elwt.set_control_flow(match wakeup {
+                                Some(instant) => ControlFlow::WaitUntil(instant),
+                                None => ControlFlow::Wait,
+                            });
                         }
                         Ok(Poll::Ready(())) => elwt.exit(),
                         Err(e) => {
@@ -195,8 +207,62 @@ impl Application {
 
         Ok(())
     }
+}
+
+/// Runs the game without a window until it ends, its replay runs out or it waits for input which
+/// can't come. A real time clock is waited for, the other ones are run as fast as possible.
+pub fn run_headless(mut game: GameEngine) -> Result<()> {
+    let replay = game.is_replaying();
+
+    while game.step()?.is_pending() {
+        if replay && !game.is_replaying() {
+            break;
+        }
+
+        let Some(wakeup) = game.next_wakeup() else {
+            break;
+        };
+
+        if game.clock().mode() == ClockMode::RealTime {
+            thread::sleep(wakeup.saturating_duration_since(Instant::now()));
+        }
+    }
+
+    eprintln!(
+        "{} steps, {:?} of game time",
+        game.steps(),
+        game.clock().now()
+    );
 
-    pub fn input(&self) -> Rc<RefCell<InputHelper>> {
-        Rc::clone(&self.input)
+    Ok(())
+}
+
+/// Resizes the window to the scale of the screen, returns `false` if it wouldn't fit the monitor.
+fn resize_window(
+    window: &Window,
+    surface: &mut Surface<Rc<Window>, Rc<Window>>,
+    scale: u32,
+) -> bool {
+    let Some(mon) = window.current_monitor() else {
+        return false;
+    };
+
+    let size = PhysicalSize::new(SCREEN_WIDTH * scale, SCREEN_HEIGHT * scale);
+
+    if size >= mon.size() {
+        return false;
     }
+
+    let Some(size) = window.request_inner_size(size) else {
+        return false;
+    };
+
+    surface
+        .resize(
+            size.width.try_into().unwrap(),
+            size.height.try_into().unwrap(),
+        )
+        .unwrap();
+
+    true
 }
@@ -0,0 +1,151 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::path::PathBuf;
+
+use crate::{
+    game::{Launch, Screen},
+    ARCHIVE_FILE_NAME,
+};
+
+pub const USAGE: &str = "\
+Usage: lotus3 [OPTIONS]
+
+Options:
+  --data <FILE>          Path of the game data [default: lotus.dat]
+  --skip-protection      Don't ask for the code of the code wheel, as the skip_protection
+                         setting does. Without the codes, which are read from codewheel.txt
+                         next to the game data, it's skipped with a notice
+  --skip-intro           Don't show the intro
+  --screen <SCREEN>      Start at the screen: protection, intro, demo, menu, select-model,
+                         audio-tuner
+  --scale <N>            Scale the window N times, 1 to 4
+  --fullscreen           Start in fullscreen
+  --set <KEY=VALUE>      Override an option for this run, e.g. --set course=t2, the settings
+                         aren't saved then
+  --no-settings          Neither load nor save the settings, the scores and the ghosts
+  --record <FILE>        Record the run into the file. The game moves by a tick per step then,
+                         paced to the wall clock in a window, so a machine which can't keep
+                         up slows the game down instead of skipping ticks
+  --replay <FILE>        Replay the run recorded in the file
+  --record-demo          Record a demo of every race played into the demos directory. No
+                         demos ship with the game, the attract mode plays these ones
+  --headless             Run without a window, as fast as the clock allows
+  -h, --help             Print this help
+";
+
+/// The command line options of the game binary.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Options {
+    pub data: PathBuf,
+    pub launch: Launch,
+    pub scale: Option<u32>,
+    pub fullscreen: bool,
+    pub overrides: Vec<(String, String)>,
+    pub no_settings: bool,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub record_demo: bool,
+    pub headless: bool,
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            data: PathBuf::from(ARCHIVE_FILE_NAME),
+            launch: Launch::default(),
+            scale: None,
+            fullscreen: false,
+            overrides: Vec::new(),
+            no_settings: false,
+            record: None,
+            replay: None,
+            record_demo: false,
+            headless: false,
+            help: false,
+        }
+    }
+}
+
+impl Options {
+    /// Parses the arguments without the name of the binary. Both `--key value` and `--key=value`
+    /// are accepted.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| anyhow!("Option '{name}' needs a value!"))
+            };
+
+            match name {
+                "--data" => options.data = value()?.into(),
+                "--skip-protection" => options.launch.skip_protection = true,
+                "--skip-intro" => options.launch.skip_intro = true,
+                "--screen" => {
+                    let value = value()?;
+
+                    options.launch.screen = Screen::ALL
+                        .iter()
+                        .find(|(_, x)| *x == value)
+                        .map(|(screen, _)| *screen)
+                        .ok_or_else(|| anyhow!("Unknown screen '{value}'!"))?;
+                }
+                "--scale" => {
+                    let value = value()?;
+                    let scale = value
+                        .parse()
+                        .ok()
+                        .filter(|x| (1..=4).contains(x))
+                        .ok_or_else(|| anyhow!("Invalid scale '{value}'!"))?;
+
+                    options.scale = Some(scale);
+                }
+                "--fullscreen" => options.fullscreen = true,
+                "--set" => {
+                    let value = value()?;
+                    let (key, value) = value
+                        .split_once('=')
+                        .with_context(|| format!("Expected 'key=value' instead of '{value}'!"))?;
+
+                    options.overrides.push((key.to_string(), value.to_string()));
+                }
+                "--no-settings" => options.no_settings = true,
+                "--record" => options.record = Some(value()?.into()),
+                "--replay" => options.replay = Some(value()?.into()),
+                "--record-demo" => options.record_demo = true,
+                "--headless" => options.headless = true,
+                "-h" | "--help" => options.help = true,
+                _ => bail!("Unknown option '{arg}'!"),
+            }
+
+            if inline.is_some() && FLAGS.contains(&name) {
+                bail!("Option '{name}' takes no value!");
+            }
+        }
+
+        if options.record.is_some() && options.replay.is_some() {
+            bail!("Options '--record' and '--replay' can't be used together!");
+        }
+
+        Ok(options)
+    }
+}
+
+const FLAGS: &[&str] = &[
+    "--skip-protection",
+    "--skip-intro",
+    "--fullscreen",
+    "--no-settings",
+    "--record-demo",
+    "--headless",
+    "--help",
+];
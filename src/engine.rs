@@ -1,46 +1,378 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use std::{
-    cell::RefCell,
-    future::Future,
-    pin::Pin,
-    rc::Rc,
-    task::{Context, Poll},
+    cell::RefCell, collections::VecDeque, fs::File, future::Future, io::BufWriter, mem,
+    path::PathBuf, pin::Pin, rc::Rc, task::Poll, time::Instant,
 };
+use winit::event::ElementState;
 
-use crate::{data::Archive, game::options::Config, input::InputHelper, task::Signal};
+use crate::{
+    data::Archive,
+    game::{code_wheel::CodeWheel, options::Config, scores::Scores, Launch},
+    input::{
+        Combined, Devices, InputDevice, InputEvent, InputHelper, InputSession, Keyboard, MenuInput,
+        RecordedEvent, Recorder, Recording,
+    },
+    rng::Rng,
+    task::{Clock, ClockMode, Executor, JoinHandle, Stats},
+};
+
+type GameMain = Box<dyn FnOnce(State) -> Pin<Box<dyn Future<Output = Result<()>>>>>;
 
 pub struct State {
     pub arc: Archive,
     pub cfg: Config,
     pub input: Rc<RefCell<InputHelper>>,
+    pub devices: Devices,
+    pub session: Rc<RefCell<InputSession>>,
+    pub rng: Rng,
+    pub record_demo: bool,
+    pub settings: Option<PathBuf>,
+    pub scores: Scores,
+    /// Where the scores are saved, they are kept for the run only without it.
+    pub scores_path: Option<PathBuf>,
+    /// The directory of the ghost cars, none are raced against without it.
+    pub ghosts: Option<PathBuf>,
+    /// The codes of the code wheel, the protection is skipped without them.
+    pub code_wheel: Option<CodeWheel>,
+    pub launch: Launch,
 }
 
+impl State {
+    /// Returns the device the player drives with: the keyboard with the player's bindings, along
+    /// with the connected device of the same number if there is one.
+    pub fn device(&self, player: usize) -> Rc<dyn InputDevice> {
+        let keyboard = Rc::new(Keyboard::new(
+            Rc::clone(&self.input),
+            self.cfg.controls.player(player).clone(),
+        ));
+
+        match self.connected(player) {
+            Some(device) => Rc::new(Combined::new(vec![keyboard, device])),
+            None => keyboard,
+        }
+    }
+
+    /// Returns what the menus read: the keys and the connected device of player 1.
+    pub fn menu_input(&self) -> MenuInput {
+        MenuInput::new(self.cfg.controls.player(0).clone(), self.connected(0))
+    }
+
+    /// Returns the connected device of the player. Playbacks are driven by the keyboard alone.
+    fn connected(&self, player: usize) -> Option<Rc<dyn InputDevice>> {
+        match self.session.borrow().is_playing() {
+            true => None,
+            false => self.devices.get(player),
+        }
+    }
+}
+
+/// Runs the game task. The game starts with the first step, until then its state can be set up
+/// by the `with_*` functions.
 pub struct GameEngine {
-    task: Pin<Box<dyn Future<Output = Result<()>>>>,
+    executor: Executor,
+    task: Option<JoinHandle<Result<()>>>,
+    start: Option<(State, GameMain)>,
+    input: Rc<RefCell<InputHelper>>,
+    devices: Devices,
+    session: Rc<RefCell<InputSession>>,
+    pending: Vec<InputEvent>,
+    steps: u64,
+    record_to: Option<PathBuf>,
+    recorder: Option<Recorder<BufWriter<File>>>,
+    playback: VecDeque<RecordedEvent>,
 }
 
 impl GameEngine {
     pub fn new<T: Future<Output = Result<()>> + 'static>(
         arc: Archive,
         cfg: Config,
-        input: Rc<RefCell<InputHelper>>,
+        clock: Clock,
         f: fn(State) -> T,
     ) -> Result<Self> {
-        let state = State { arc, cfg, input };
+        let input = Rc::new(RefCell::new(InputHelper::new()));
+        let devices = Devices::default();
+        let session = Rc::new(RefCell::new(InputSession::default()));
+
+        let state = State {
+            arc,
+            cfg,
+            input: Rc::clone(&input),
+            devices: devices.clone(),
+            session: Rc::clone(&session),
+            rng: Rng::from_time(),
+            record_demo: false,
+            settings: None,
+            scores: Scores::default(),
+            scores_path: None,
+            ghosts: None,
+            code_wheel: None,
+            launch: Launch::default(),
+        };
+
+        let main: GameMain = Box::new(move |state| Box::pin(f(state)));
 
         Ok(Self {
-            task: Box::pin(f(state)),
+            executor: Executor::with_clock(clock),
+            task: None,
+            start: Some((state, main)),
+            input,
+            devices,
+            session,
+            pending: Vec::new(),
+            steps: 0,
+            record_to: None,
+            recorder: None,
+            playback: VecDeque::new(),
         })
     }
 
-    pub fn step(&mut self, ctx: &mut Context<'static>, signal: &Signal) -> Result<Poll<()>> {
-        Ok(match self.task.as_mut().poll(ctx) {
-            Poll::Pending => {
-                signal.wait();
+    /// Connects the devices, the first one goes to player 1.
+    pub fn with_devices<D: InputDevice + 'static>(
+        self,
+        devices: impl IntoIterator<Item = D>,
+    ) -> Self {
+        for device in devices {
+            self.devices.connect(Rc::new(device));
+        }
+
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        if let Some((state, _)) = &mut self.start {
+            state.rng = Rng::new(seed);
+        }
+
+        self
+    }
+
+    pub fn with_launch(mut self, launch: Launch) -> Self {
+        if let Some((state, _)) = &mut self.start {
+            state.launch = launch;
+        }
+
+        self
+    }
+
+    /// Lets the main menu save the options into the settings file.
+    pub fn with_settings(mut self, path: impl Into<PathBuf>) -> Self {
+        if let Some((state, _)) = &mut self.start {
+            state.settings = Some(path.into());
+        }
+
+        self
+    }
+
+    /// Starts with the scores and lets the game save the new ones into the file.
+    pub fn with_scores(mut self, scores: Scores, path: impl Into<PathBuf>) -> Self {
+        if let Some((state, _)) = &mut self.start {
+            state.scores = scores;
+            state.scores_path = Some(path.into());
+        }
+
+        self
+    }
+
+    pub fn with_code_wheel(mut self, wheel: CodeWheel) -> Self {
+        if let Some((state, _)) = &mut self.start {
+            state.code_wheel = Some(wheel);
+        }
+
+        self
+    }
+
+    /// Lets the game race against the best laps kept in the directory and save new ones there.
+    pub fn with_ghosts(mut self, dir: impl Into<PathBuf>) -> Self {
+        if let Some((state, _)) = &mut self.start {
+            state.ghosts = Some(dir.into());
+        }
+
+        self
+    }
+
+    /// Lets the game record a demo of every race played.
+    pub fn with_demo_recording(mut self) -> Self {
+        if let Some((state, _)) = &mut self.start {
+            state.record_demo = true;
+        }
+
+        self
+    }
+
+    /// Records the run into the file. The clock has to be a fixed step one, so the replay sees
+    /// the same time at every step.
+    pub fn with_recorder(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record_to = Some(path.into());
+        self
+    }
+
+    /// Replays the recording: the options, the seed and the clock step are taken from it, and the
+    /// keyboard is ignored until the recorded events run out.
+    pub fn with_playback(mut self, recording: Recording) -> Self {
+        if let Some((state, _)) = &mut self.start {
+            state.cfg = recording.cfg;
+            state.rng = Rng::new(recording.seed);
+        }
+
+        self.executor = Executor::with_clock(Clock::fixed_step(recording.step));
+        self.playback = recording.events.into();
+
+        self
+    }
+
+    /// Queues a keyboard event for the next step.
+    pub fn handle_event(&mut self, event: InputEvent) {
+        self.pending.push(event);
+    }
 
-                Poll::Pending
+    /// Releases every held key, e.g. when the window loses focus and the releases won't come.
+    pub fn release_all(&mut self) {
+        self.pending.extend(releases(&self.input.borrow()));
+    }
+
+    pub fn step(&mut self) -> Result<Poll<()>> {
+        if let Some((state, main)) = self.start.take() {
+            self.start_recording(&state)?;
+            self.task = Some(self.executor.spawn(main(state)));
+        }
+
+        let live = mem::take(&mut self.pending);
+
+        let events = if self.playback.is_empty() {
+            live
+        } else {
+            let count = self
+                .playback
+                .iter()
+                .take_while(|x| x.step <= self.steps)
+                .count();
+
+            self.playback.drain(..count).map(|x| x.event).collect()
+        };
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(self.steps, &events)?;
+        }
+
+        let now = self.executor.clock().now();
+        let (mut events, ended) = self.session.borrow_mut().feed(now, events);
+
+        // the presses add up until a tick has seen them, so a step without a tick doesn't lose
+        // them and a step of several ticks shows them to the first one only
+        let mut input = self.input.borrow_mut();
+        input.update(now);
+
+        if ended {
+            // the keys held by the playback would stay down forever
+            events.extend(releases(&input));
+        }
+
+        for event in events {
+            input.handle_event(event);
+        }
+
+        // tasks don't register for input, so let every one of them see the new keys
+        if input.has_events() {
+            self.executor.wake_all();
+        }
+
+        drop(input);
+
+        let mut ticks = 0;
+
+        // the devices are sampled once per tick, for the same reason
+        self.executor.run_with(|| {
+            if ticks > 0 {
+                self.input.borrow_mut().clear();
             }
-            Poll::Ready(result) => Poll::Ready(result?),
+
+            self.devices.update();
+            ticks += 1;
+        });
+
+        if ticks > 0 {
+            self.input.borrow_mut().clear();
+        }
+
+        self.steps += 1;
+
+        let task = self.task.as_mut().expect("the game has been started");
+
+        Ok(match task.try_join() {
+            Some(result) => Poll::Ready(result?),
+            None => Poll::Pending,
         })
     }
+
+    fn start_recording(&mut self, state: &State) -> Result<()> {
+        let Some(path) = self.record_to.take() else {
+            return Ok(());
+        };
+
+        let ClockMode::FixedStep(step) = self.executor.clock().mode() else {
+            bail!("Recording needs a fixed step clock!");
+        };
+
+        let header = Recording::new(state.rng.seed(), step, state.cfg.clone());
+        self.recorder = Some(Recorder::create(&path, &header)?);
+
+        Ok(())
+    }
+
+    /// Whether recorded events are still to be replayed.
+    pub fn is_replaying(&self) -> bool {
+        !self.playback.is_empty()
+    }
+
+    /// The number of steps made so far.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn clock(&self) -> &Clock {
+        self.executor.clock()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.executor.stats()
+    }
+
+    pub fn frame_presented(&self) {
+        self.executor.frame_presented();
+    }
+
+    /// Returns the time of the next step, `None` if the game waits for input only.
+    pub fn next_wakeup(&self) -> Option<Instant> {
+        let clock = self.executor.clock();
+
+        let repeat = self
+            .input
+            .borrow()
+            .next_repeat()
+            .and_then(|x| clock.to_instant(x));
+
+        // the recorded events come at their steps, not at any time a task waits for
+        let playback = (!self.playback.is_empty())
+            .then(|| clock.to_instant(clock.now()))
+            .flatten();
+
+        self.executor
+            .next_wakeup()
+            .into_iter()
+            .chain(repeat)
+            .chain(playback)
+            .min()
+    }
+}
+
+/// Returns the events which release every held key.
+fn releases(input: &InputHelper) -> Vec<InputEvent> {
+    input
+        .held()
+        .map(|key| InputEvent {
+            key: key.clone(),
+            state: ElementState::Released,
+            repeat: false,
+        })
+        .collect()
 }
@@ -0,0 +1,126 @@
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use std::{fs, path::Path};
+
+use crate::{graphics::Size, rng::Rng};
+
+/// The file of the codes of the wheel, next to the game data.
+pub const CODE_WHEEL_FILE: &str = "codewheel.txt";
+
+/// The width and the height of a helmet of `I22`, in pixels.
+pub const HELMET_SIZE: Size = Size::wh(48, 40);
+
+pub const CODE_LEN: usize = 3;
+
+/// The number of codes the player may enter before the game quits. How many the original allows
+/// hasn't been checked, three is this port's choice.
+pub const TRIES: u32 = 3;
+
+/// A question of the code wheel: the player turns the inner ring until the `bottom` helmet is
+/// under the `top` one and reads the code in the window.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Challenge {
+    pub window: u32,
+    pub top: usize,
+    pub bottom: usize,
+}
+
+impl Challenge {
+    /// How many of the `helmets` the inner ring is turned by to bring the `bottom` helmet under
+    /// the `top` one, counted from the position in which every helmet is under its own.
+    pub fn turn(&self, helmets: usize) -> usize {
+        (self.bottom + helmets - self.top) % helmets
+    }
+}
+
+/// The codes printed on the code wheel which came with the game. They aren't part of the game
+/// data, so the owners of a wheel transcribe them into [`CODE_WHEEL_FILE`]: a line per window
+/// with its number and the codes it shows for the turns of the inner ring from 0 on, one per
+/// helmet of `I22`, see [`Challenge::turn`]. Empty lines and lines starting with `#` are
+/// skipped.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CodeWheel {
+    windows: Vec<u32>,
+    codes: Vec<Vec<String>>, // by window, in the order of `windows`
+}
+
+impl CodeWheel {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut windows = Vec::new();
+        let mut codes = Vec::<Vec<String>>::new();
+
+        for line in text.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+
+            let window = words
+                .next()
+                .and_then(|x| x.parse().ok())
+                .ok_or_else(|| anyhow!("Expected the number of a window in '{line}'!"))?;
+
+            ensure!(!windows.contains(&window), "Window repeated in '{line}'!");
+
+            let line_codes = words.map(|x| x.to_ascii_uppercase()).collect::<Vec<_>>();
+            let helmets = codes.first().map_or(line_codes.len().max(2), Vec::len);
+
+            ensure!(
+                line_codes.len() == helmets
+                    && line_codes.iter().all(
+                        |x| x.len() == CODE_LEN && x.bytes().all(|c| c.is_ascii_alphanumeric())
+                    ),
+                "Expected {helmets} codes of {CODE_LEN} letters or digits in '{line}'!"
+            );
+
+            windows.push(window);
+            codes.push(line_codes);
+        }
+
+        if windows.is_empty() {
+            bail!("The codes of the windows are missing!");
+        }
+
+        Ok(Self { windows, codes })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read '{}'!", path.display()))?;
+
+        Self::parse(&text).with_context(|| format!("Invalid code wheel '{}'!", path.display()))
+    }
+
+    /// The numbers of the windows cut into the wheel.
+    pub fn windows(&self) -> &[u32] {
+        &self.windows
+    }
+
+    /// The number of helmets around each ring of the wheel.
+    pub fn helmets(&self) -> usize {
+        self.codes[0].len()
+    }
+
+    /// Draws a window and two different helmets.
+    pub fn challenge(&self, rng: &mut Rng) -> Challenge {
+        let helmets = self.helmets() as u32;
+        let top = rng.below(helmets);
+
+        Challenge {
+            window: self.windows[rng.below(self.windows.len() as u32) as usize],
+            top: top as usize,
+            bottom: ((top + 1 + rng.below(helmets - 1)) % helmets) as usize,
+        }
+    }
+
+    /// Returns the code the window shows for the challenge.
+    pub fn code(&self, challenge: &Challenge) -> &str {
+        let index = self
+            .windows
+            .iter()
+            .position(|x| *x == challenge.window)
+            .expect("the challenge has a window of the wheel");
+
+        &self.codes[index][challenge.turn(self.helmets())]
+    }
+}
@@ -0,0 +1,191 @@
+use anyhow::Result;
+use std::{
+    cell::RefCell,
+    fs,
+    future::poll_fn,
+    mem,
+    path::Path,
+    rc::Rc,
+    task::Poll,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::{play, show_lotus_logo, show_scores, Screen};
+use crate::{
+    engine::State,
+    graphics::font::{Font, CHAR_SET_03},
+    input::{InputHelper, InputSession, Recording},
+    rng::Rng,
+    screen::{fade_out, screen_at},
+    task::{now, select, tick, Either, TICK},
+    DEMOS_DIR,
+};
+
+const OVERLAY_POS: (u32, u32) = (142, 4);
+
+/// Loads every `.rec` file of the directory in the order of their names. The broken ones are
+/// reported and skipped, so a bad file doesn't take the game down.
+pub fn load_demos(dir: &Path) -> Vec<Recording> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paths = entries
+        .filter_map(|x| Some(x.ok()?.path()))
+        .filter(|x| x.extension().is_some_and(|ext| ext == "rec"))
+        .collect::<Vec<_>>();
+
+    paths.sort();
+
+    paths
+        .iter()
+        .filter_map(|path| match Recording::load(path) {
+            Ok(demo) => Some(demo),
+            Err(e) => {
+                eprintln!("{e:?}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Shows the Lotus logo, the demos and the score table in turn until a key is pressed. There are
+/// only the demos recorded into [`DEMOS_DIR`], without any the logo and the scores are shown once.
+pub async fn attract_mode(state: &mut State, demos: &[Recording]) -> Result<()> {
+    if demos.is_empty() {
+        // nothing to play, the logo and the scores are all there is
+        if show_logo(state).await? {
+            show_score_table(state).await?;
+        }
+
+        return Ok(());
+    }
+
+    for demo in demos.iter().cycle() {
+        if !show_logo(state).await?
+            || !play_demo(state, demo).await?
+            || !show_score_table(state).await?
+        {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Shows the logo, returns `false` if a key has been pressed meanwhile.
+async fn show_logo(state: &mut State) -> Result<bool> {
+    let input = Rc::clone(&state.input);
+
+    match select(show_lotus_logo(state), any_key(&input)).await {
+        Either::Left(result) => result.map(|_| true),
+        Either::Right(()) => {
+            fade_out().await;
+
+            Ok(false)
+        }
+    }
+}
+
+/// Shows the score table, returns `false` if a key has been pressed meanwhile.
+async fn show_score_table(state: &mut State) -> Result<bool> {
+    // there is nothing to show before the first race
+    if state.scores.is_empty() {
+        return Ok(true);
+    }
+
+    let input = Rc::clone(&state.input);
+
+    match select(show_scores(state), any_key(&input)).await {
+        Either::Left(result) => result.map(|_| true),
+        Either::Right(()) => {
+            fade_out().await;
+
+            Ok(false)
+        }
+    }
+}
+
+async fn any_key(input: &RefCell<InputHelper>) {
+    // the engine wakes every task on input, so there is no waker to register
+    poll_fn(|_| match input.borrow().any_pressed() {
+        true => Poll::Ready(()),
+        false => Poll::Pending,
+    })
+    .await
+}
+
+/// Plays the demo with its options and seed, returns `false` if a key has been pressed
+/// meanwhile. The options of the player are restored afterwards.
+pub async fn play_demo(state: &mut State, demo: &Recording) -> Result<bool> {
+    let session = Rc::clone(&state.session);
+    let font = Font::from(CHAR_SET_03, state.arc.get("C03")?);
+    let (_, pal) = state.arc.get_with_palette("I14")?;
+
+    let cfg = mem::replace(&mut state.cfg, demo.cfg.clone());
+    let rng = mem::replace(&mut state.rng, Rng::new(demo.seed));
+
+    session.borrow_mut().play(demo, now());
+
+    let result = match select(
+        play(state, Screen::SelectModel),
+        overlay(&session, &font, &pal),
+    )
+    .await
+    {
+        Either::Left(result) => result.map(|_| ()),
+        Either::Right(()) => Ok(()),
+    };
+
+    let interrupted = session.borrow().interrupted();
+    session.borrow_mut().stop();
+
+    state.cfg = cfg;
+    state.rng = rng;
+
+    fade_out().await;
+
+    result.map(|_| !interrupted)
+}
+
+/// Keeps "DEMO" on the screen until the playback is over or interrupted.
+async fn overlay(session: &RefCell<InputSession>, font: &Font, pal: &[u8]) {
+    loop {
+        font.print(screen_at(OVERLAY_POS), "DEMO", pal);
+
+        tick().await;
+
+        let session = session.borrow();
+
+        if !session.is_playing() || session.interrupted() {
+            break;
+        }
+    }
+}
+
+/// Plays the game like [`play`] does, recording it as a demo into [`DEMOS_DIR`].
+pub async fn record_demo(state: &mut State, from: Screen) -> Result<bool> {
+    // a seed of its own, so the demo doesn't depend on how the generator got here
+    let seed = state.rng.next_u64();
+    state.rng = Rng::new(seed);
+
+    let mut demo = Recording::new(seed, TICK, state.cfg.clone());
+
+    state.session.borrow_mut().record(TICK, now());
+
+    let result = play(state, from).await;
+
+    demo.events = state.session.borrow_mut().take_recording();
+
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs());
+
+    fs::create_dir_all(DEMOS_DIR)?;
+    fs::write(
+        Path::new(DEMOS_DIR).join(format!("demo-{secs}.rec")),
+        demo.to_string(),
+    )?;
+
+    result
+}
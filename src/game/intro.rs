@@ -1,101 +1,125 @@
 use anyhow::Result;
-use winit::keyboard::NamedKey;
+use std::{cell::RefCell, future::poll_fn, rc::Rc, task::Poll};
 
 use crate::{
     engine::State,
     graphics::{Canvas, Color, Point, Size, Sprite, SpriteFont},
+    input::{Action, InputHelper, MenuInput},
     screen::{
         fade_in, fade_in_only, fade_out, fade_out_by_color, fade_out_only, screen, screen_at,
         screen_copy,
     },
-    task::sleep,
+    task::{sleep, spawn, CancelReason, CancellationToken},
 };
 
-macro_rules! cancel_fn {
-    ($state:expr) => {
-        || {
-            let input = $state.input.borrow();
-
-            input.key_pressed(NamedKey::Enter)
-                || input.key_pressed(NamedKey::Escape)
-                || input.key_pressed(NamedKey::Space)
+/// Plays the intro screens one by one. Any of the skip keys cancels the rest of the intro.
+pub async fn show_intro(state: &mut State) -> Result<()> {
+    let token = CancellationToken::new();
+    let skip = spawn(skip_on_keys(
+        Rc::clone(&state.input),
+        state.menu_input(),
+        token.clone(),
+    ));
+
+    let intro = token
+        .run(async {
+            show_gremlin(state).await?;
+            show_magnetic_fields(state).await?;
+            show_credits(state).await?;
+            show_lotus_logo(state).await?;
+            show_magazine(state).await
+        })
+        .await;
+
+    skip.abort();
+
+    match intro {
+        Ok(result) => result,
+        Err(_) => {
+            fade_out().await;
+
+            Ok(())
         }
-    };
+    }
 }
 
-macro_rules! try_ok {
-    ($token:expr) => {
-        if $token {
-            return Ok(false);
+/// Cancels the token as soon as player 1 presses Confirm or Back.
+pub async fn skip_on_keys(
+    input: Rc<RefCell<InputHelper>>,
+    menu: MenuInput,
+    token: CancellationToken,
+) {
+    // the engine wakes every task on input, so there is no waker to register
+    poll_fn(|_| {
+        let input = input.borrow();
+
+        if menu.pressed(&input, Action::Confirm) || menu.pressed(&input, Action::Back) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
         }
-    };
+    })
+    .await;
+
+    token.cancel(CancelReason::Skipped);
 }
 
-pub async fn show_gremlin(state: &mut State) -> Result<bool> {
+pub async fn show_gremlin(state: &mut State) -> Result<()> {
     const SPLASH_SIZE: Size = Size::wh(16, 8);
 
     let (q00, ref pal) = state.arc.get_with_palette("Q00")?;
 
     Sprite::from(q00).draw(screen(), pal);
 
-    let cancel = cancel_fn!(state);
-    try_ok!(fade_in(Some(&cancel)).await);
-    try_ok!(sleep(200).with_cancel(&cancel).await);
+    fade_in().await;
+    sleep(200).await;
 
     let stars = state
         .arc
         .get_series("Q01", SPLASH_SIZE.width * SPLASH_SIZE.height)?;
 
     for i in [0, 1, 2, 3, 2, 1, 0] {
-        let timer = sleep(100).with_cancel(&cancel);
-
         Sprite::from(stars[i].to_vec())
             .with_size(SPLASH_SIZE)
             .draw(screen_at((112, 85)), pal);
 
-        try_ok!(timer.await);
+        sleep(100).await;
     }
 
     for i in [4, 5, 6, 7, 6, 5, 4] {
-        let timer = sleep(100).with_cancel(&cancel);
-
         Sprite::from(stars[i].to_vec())
             .with_size(SPLASH_SIZE)
             .draw(screen_at((144, 110)), pal);
 
-        try_ok!(timer.await);
+        sleep(100).await;
     }
 
-    try_ok!(fade_out(Some(&cancel)).await);
+    fade_out().await;
 
-    Ok(true)
+    Ok(())
 }
 
-pub async fn show_magnetic_fields(state: &mut State) -> Result<bool> {
+pub async fn show_magnetic_fields(state: &mut State) -> Result<()> {
     const KEYS: [&str; 22] = [
         "Q02", "Q03", "Q04", "Q05", "Q06", "Q07", "Q08", "Q09", "Q0A", "Q0B", "Q0C", "Q0D", "Q0E",
         "Q0F", "Q10", "Q11", "Q12", "Q13", "Q14", "Q15", "Q16", "Q17",
     ];
 
-    let cancel = cancel_fn!(state);
-
     let (_, ref pal) = state.arc.get_with_palette(KEYS.last().unwrap())?;
 
     for key in KEYS {
-        let timer = sleep(50).with_cancel(&cancel);
-
         Sprite::from(state.arc.get(key)?).draw(screen(), pal);
 
-        try_ok!(timer.await);
+        sleep(50).await;
     }
 
-    try_ok!(sleep(1000).with_cancel(&cancel).await);
-    try_ok!(fade_out(Some(&cancel)).await);
+    sleep(1000).await;
+    fade_out().await;
 
-    Ok(true)
+    Ok(())
 }
 
-pub async fn show_credits(state: &mut State) -> Result<bool> {
+pub async fn show_credits(state: &mut State) -> Result<()> {
     const CREDITS_FADE_IN_TIMEOUT: u64 = 2000;
     const CREDITS_FADE_OUT_TIMEOUT: u64 = 1000;
     const CREDITS: [&[(&str, u32, u32)]; 5] = [
@@ -138,9 +162,8 @@ pub async fn show_credits(state: &mut State) -> Result<bool> {
 
     bgr.draw(screen(), pal);
 
-    let cancel = cancel_fn!(state);
-    try_ok!(fade_in(Some(&cancel)).await);
-    try_ok!(sleep(2000).with_cancel(&cancel).await);
+    fade_in().await;
+    sleep(2000).await;
 
     let font = SpriteFont::from(state.arc.get("Q1A")?);
     let back = screen_copy();
@@ -152,30 +175,26 @@ pub async fn show_credits(state: &mut State) -> Result<bool> {
             font.print(front.raw_at((*x, *y)), text);
         }
 
-        try_ok!(fade_in_only(&back, &front, Some(&cancel)).await);
-        try_ok!(sleep(CREDITS_FADE_IN_TIMEOUT).with_cancel(&cancel).await);
+        fade_in_only(&back, &front).await;
+        sleep(CREDITS_FADE_IN_TIMEOUT).await;
 
-        try_ok!(fade_out_only(&back, &front, Some(&cancel)).await);
-        try_ok!(sleep(CREDITS_FADE_OUT_TIMEOUT).with_cancel(&cancel).await);
+        fade_out_only(&back, &front).await;
+        sleep(CREDITS_FADE_OUT_TIMEOUT).await;
     }
 
     let q1b = state.arc.get("Q1B")?;
 
     for step in 1..=36 {
-        let timer = sleep(50).with_cancel(&cancel);
-
         bgr.draw(screen(), pal);
         draw_a_car(screen(), &q1b, pal, step);
 
-        try_ok!(timer.await);
+        sleep(50).await;
     }
 
     for key in ["Q1C", "Q1D"] {
-        let timer = sleep(50).with_cancel(&cancel);
-
         Sprite::from(state.arc.get(key)?).draw(screen(), pal);
 
-        try_ok!(timer.await);
+        sleep(50).await;
     }
 
     let q1e = state.arc.get("Q1E")?;
@@ -183,17 +202,17 @@ pub async fn show_credits(state: &mut State) -> Result<bool> {
 
     Sprite::from(q1e).draw(screen(), pal);
 
-    try_ok!(sleep(2000).with_cancel(&cancel).await);
+    sleep(2000).await;
 
     let color = pal
         .get(color_ix * 3..color_ix * 3 + 3)
         .map(|rgb| Color::rgb(rgb[0] << 2, rgb[1] << 2, rgb[2] << 2));
 
-    try_ok!(fade_out_by_color(color.unwrap(), Some(&cancel)).await);
-    try_ok!(sleep(4000).with_cancel(&cancel).await);
-    try_ok!(fade_out(Some(&cancel)).await);
+    fade_out_by_color(color.unwrap()).await;
+    sleep(4000).await;
+    fade_out().await;
 
-    Ok(true)
+    Ok(())
 }
 
 fn draw_a_car(canvas: &mut [u32], data: &[u8], pal: &[u8], step: usize) {
@@ -204,13 +223,12 @@ fn draw_a_car(canvas: &mut [u32], data: &[u8], pal: &[u8], step: usize) {
         - (((((WIDTH * 118) + 288) / cx) * (WIDTH * 32 + 170)) >> 16) * WIDTH;
 
     let xx = di % WIDTH;
-    let mut y = di / WIDTH;
+    let yy = di / WIDTH;
 
-    for row in (0..WIDTH * 118).step_by(cx) {
+    for (y, row) in (yy..).zip((0..WIDTH * 118).step_by(cx)) {
         let offset = (row >> 8) * 224;
-        let mut x = xx;
 
-        for i in (offset..offset + 224).step_by(cx >> 8) {
+        for (x, i) in (xx..).zip((offset..offset + 224).step_by(cx >> 8)) {
             let px = data[i] as usize;
 
             if px != 0xFF {
@@ -221,28 +239,23 @@ fn draw_a_car(canvas: &mut [u32], data: &[u8], pal: &[u8], step: usize) {
                     pal[px * 3 + 2] << 2,
                 ]);
             }
-
-            x += 1;
         }
-
-        y += 1;
     }
 }
 
-pub async fn show_lotus_logo(state: &mut State) -> Result<bool> {
+pub async fn show_lotus_logo(state: &mut State) -> Result<()> {
     let (q18, ref pal) = state.arc.get_with_palette("Q18")?;
 
     Sprite::from(q18).draw(screen(), pal);
 
-    let cancel = cancel_fn!(state);
-    try_ok!(fade_in(Some(&cancel)).await);
-    try_ok!(sleep(2000).with_cancel(&cancel).await);
-    try_ok!(fade_out(Some(&cancel)).await);
+    fade_in().await;
+    sleep(2000).await;
+    fade_out().await;
 
-    Ok(true)
+    Ok(())
 }
 
-pub async fn show_magazine(state: &mut State) -> Result<bool> {
+pub async fn show_magazine(state: &mut State) -> Result<()> {
     const VIDEO_SIZE: Size = Size::wh(160, 112);
     const VIDEO_POS: Point = Point::xy(136, 38);
 
@@ -271,18 +284,14 @@ pub async fn show_magazine(state: &mut State) -> Result<bool> {
     bgr.draw(back.raw(), pal);
     bgr.draw(screen(), pal);
 
-    let cancel = cancel_fn!(state);
-
     for key in KEYS {
-        let timer = sleep(100).with_cancel(&cancel);
-
         let (vxx, ref pal) = get_with_leading_pal(state.arc.get(key)?, pal)?;
         let vxx = Sprite::from(vxx).with_size(VIDEO_SIZE);
 
         vxx.draw(screen_at(VIDEO_POS), pal);
 
         if Some(&key) == KEYS.first() {
-            try_ok!(fade_in(Some(&cancel)).await);
+            fade_in().await;
         } else {
             // save the last frame
             if Some(&key) == KEYS.last() {
@@ -290,10 +299,10 @@ pub async fn show_magazine(state: &mut State) -> Result<bool> {
             }
         }
 
-        try_ok!(timer.await);
+        sleep(100).await;
     }
 
-    try_ok!(fade_out_only(&back, &front, Some(&cancel)).await);
+    fade_out_only(&back, &front).await;
 
     let (v33, ref pal) = get_with_leading_pal(state.arc.get("V33")?, pal)?;
 
@@ -301,9 +310,9 @@ pub async fn show_magazine(state: &mut State) -> Result<bool> {
         .with_size(VIDEO_SIZE)
         .draw(front.raw_at(VIDEO_POS), pal);
 
-    try_ok!(fade_in_only(&back, &front, Some(&cancel)).await);
-    try_ok!(sleep(2000).with_cancel(&cancel).await);
-    try_ok!(fade_out(Some(&cancel)).await);
+    fade_in_only(&back, &front).await;
+    sleep(2000).await;
+    fade_out().await;
 
-    Ok(true)
+    Ok(())
 }
@@ -0,0 +1,155 @@
+use anyhow::Result;
+use std::cell::Ref;
+use winit::keyboard::NamedKey;
+
+use crate::{
+    engine::State,
+    graphics::{
+        font::{Font, CHAR_SET_04},
+        Frame, Size,
+    },
+    input::{key_name, Action, Controls, InputHelper, MenuInput},
+    screen::{fade_in, fade_out, screen, screen_at},
+    task::tick,
+};
+
+const TITLE_POS: (u32, u32) = (132, 8);
+const ROW_TOP: u32 = 44; // y of the first action
+const ROW_HEIGHT: u32 = 17;
+const COL_ACTION: u32 = 16; // x of the action names
+const COL_PLAYER: [u32; 2] = [150, 235]; // x of the keys of each player
+const KEY_FRAME_SIZE: Size = Size::wh(60, 18);
+
+#[derive(Default)]
+struct Position {
+    row: u8,
+    player: u8,
+    waiting: bool, // the next key pressed is bound to the selected action, Escape cancels
+}
+
+pub async fn controls_menu(state: &mut State, pal: &[u8]) -> Result<()> {
+    let font = Font::from(CHAR_SET_04, state.arc.get("C04")?);
+    let frame = Frame::new(KEY_FRAME_SIZE);
+
+    let mut first_time = true;
+    let mut pos = Position::default();
+
+    loop {
+        tick().await;
+
+        let (key_pressed, exit) = handle_input(
+            state.input.borrow(),
+            &state.menu_input(),
+            &mut pos,
+            &mut state.cfg.controls,
+        );
+
+        if exit {
+            break;
+        }
+
+        if first_time || key_pressed {
+            screen().fill(0xff00_0000);
+
+            font.print(screen_at(TITLE_POS), "CONTROLS", pal);
+            font.print(screen_at((COL_PLAYER[0], ROW_TOP - 20)), "PLAYER 1", pal);
+            font.print(screen_at((COL_PLAYER[1], ROW_TOP - 20)), "PLAYER 2", pal);
+
+            for (row, action) in (0..).zip(Action::ALL) {
+                let y = ROW_TOP + row * ROW_HEIGHT;
+
+                font.print(screen_at((COL_ACTION, y)), action.name(), pal);
+
+                for (player, x) in COL_PLAYER.into_iter().enumerate() {
+                    let selected = pos.row as u32 == row && pos.player as usize == player;
+
+                    let name = if selected && pos.waiting {
+                        "-".to_string()
+                    } else {
+                        key_name(state.cfg.controls.player(player).get(action)).unwrap_or_default()
+                    };
+
+                    font.print(screen_at((x, y)), &name, pal);
+
+                    if selected {
+                        frame.draw(screen_at((x - 7, y - 5)), pal);
+                    }
+                }
+            }
+
+            if first_time {
+                first_time = false;
+
+                fade_in().await;
+            }
+        }
+    }
+
+    fade_out().await;
+
+    Ok(())
+}
+
+fn handle_input(
+    input: Ref<InputHelper>,
+    menu: &MenuInput,
+    pos: &mut Position,
+    controls: &mut Controls,
+) -> (bool, bool) {
+    let action = Action::ALL[pos.row as usize];
+
+    if pos.waiting {
+        if input.just_pressed(NamedKey::Escape) {
+            pos.waiting = false;
+
+            return (true, false);
+        }
+
+        // repeats of the key which started the wait don't count
+        let key = input
+            .keys()
+            .find(|k| input.just_pressed((*k).clone()) && key_name(k).is_some());
+
+        if let Some(key) = key {
+            controls.set(pos.player as usize, action, key.clone());
+            pos.waiting = false;
+
+            return (true, false);
+        }
+
+        return (false, false);
+    }
+
+    let mut key_pressed = false;
+
+    if menu.pressed(&input, Action::Accelerate) && pos.row > 0 {
+        pos.row -= 1;
+        key_pressed = true;
+    }
+
+    if menu.pressed(&input, Action::Brake) && (pos.row as usize) < Action::ALL.len() - 1 {
+        pos.row += 1;
+        key_pressed = true;
+    }
+
+    if menu.pressed(&input, Action::SteerLeft) && pos.player > 0 {
+        pos.player -= 1;
+        key_pressed = true;
+    }
+
+    if menu.pressed(&input, Action::SteerRight) && pos.player < 1 {
+        pos.player += 1;
+        key_pressed = true;
+    }
+
+    if menu.pressed(&input, Action::Back) {
+        return (false, true);
+    }
+
+    if menu.pressed(&input, Action::Confirm) {
+        pos.waiting = true;
+        key_pressed = true;
+    }
+
+    (key_pressed, false)
+}
@@ -1,6 +1,5 @@
 use anyhow::Result;
 use std::cell::Ref;
-use winit::keyboard::NamedKey;
 
 use super::{FRAME_OFFSET, FRAME_SIZE_4R, FRAME_SIZE_ST};
 use crate::{
@@ -9,9 +8,9 @@ use crate::{
         font::{Font, CHAR_SET_04},
         Frame, Sprite, FRAME_BORDER,
     },
-    input::InputHelper,
+    input::{Action, InputHelper, MenuInput},
     screen::{fade_in, fade_out, screen, screen_at},
-    task::yield_now,
+    task::tick,
 };
 
 #[derive(Default)]
@@ -28,9 +27,9 @@ pub async fn define_menu(state: &mut State, pal: &[u8]) -> Result<()> {
     let mut pos = Position::default();
 
     loop {
-        yield_now().await;
+        tick().await;
 
-        let (key_pressed, exit) = handle_input(state.input.borrow(), &mut pos);
+        let (key_pressed, exit) = handle_input(state.input.borrow(), &state.menu_input(), &mut pos);
 
         if exit {
             break;
@@ -67,26 +66,26 @@ pub async fn define_menu(state: &mut State, pal: &[u8]) -> Result<()> {
             if first_time {
                 first_time = false;
 
-                fade_in(None).await;
+                fade_in().await;
             }
         }
     }
 
-    fade_out(None).await;
+    fade_out().await;
 
     Ok(())
 }
 
-fn handle_input(input: Ref<InputHelper>, pos: &mut Position) -> (bool, bool) {
+fn handle_input(input: Ref<InputHelper>, menu: &MenuInput, pos: &mut Position) -> (bool, bool) {
     let mut key_pressed = false;
 
     if !pos.editor {
         match pos.row {
-            0 if input.key_pressed(NamedKey::ArrowDown) => {
+            0 if menu.pressed(&input, Action::Brake) => {
                 pos.row += 1;
                 key_pressed = true;
             }
-            1 if input.key_pressed(NamedKey::ArrowUp) => {
+            1 if menu.pressed(&input, Action::Accelerate) => {
                 pos.row -= 1;
                 key_pressed = true;
             }
@@ -94,7 +93,7 @@ fn handle_input(input: Ref<InputHelper>, pos: &mut Position) -> (bool, bool) {
         }
     }
 
-    if input.key_pressed(NamedKey::Escape) {
+    if menu.pressed(&input, Action::Back) {
         if pos.editor {
             pos.editor = false;
             key_pressed = true;
@@ -103,7 +102,7 @@ fn handle_input(input: Ref<InputHelper>, pos: &mut Position) -> (bool, bool) {
         }
     }
 
-    if input.key_pressed(NamedKey::Enter) {
+    if menu.pressed(&input, Action::Confirm) {
         match pos.row {
             0 => return (false, true),
             1 => {
@@ -1,19 +1,20 @@
 use anyhow::Result;
-use std::cell::Ref;
-use winit::keyboard::NamedKey;
+use std::{cell::Ref, time::Duration};
 
 use super::{FRAME_OFFSET, FRAME_SIZE_ST, MENU_ITEM_SIZE};
 use crate::{
     engine::State,
-    game::define_menu,
     game::options::{Acceleration, Config, Course, Race, Transmission},
+    game::recs::Track,
+    game::settings,
+    game::{controls_menu, define_menu, recs_menu},
     graphics::{
         font::{Font, CHAR_SET_03, CHAR_SET_04},
         Frame, Sprite, FRAME_BORDER,
     },
-    input::{InputHelper, BACKSPACE_CHAR},
+    input::{Action as Control, InputHelper, MenuInput, BACKSPACE_CHAR},
     screen::{fade_in, fade_out, screen, screen_at},
-    task::yield_now,
+    task::{now, tick},
 };
 
 struct Position {
@@ -32,8 +33,12 @@ impl Default for Position {
     }
 }
 
+/// How long the menu waits for a key before the demos start.
+const IDLE_TIME: Duration = Duration::from_secs(30);
+
 pub enum Action {
     Start,
+    Demo,
     Exit,
 }
 
@@ -80,12 +85,31 @@ pub async fn main_menu(state: &mut State) -> Result<Action> {
 
     let mut first_time = true;
     let mut pos = Position::default();
+    let mut idle_since = now();
+
+    // the code typed into the menu, which may be left half-done, falls back to this one
+    let mut code = state.cfg.code.clone();
 
     let action = loop {
-        yield_now().await;
+        tick().await;
+
+        if state.input.borrow().has_events() {
+            idle_since = now();
+        } else if now() - idle_since >= IDLE_TIME {
+            break Action::Demo;
+        }
 
-        let (key_pressed, action, menu) =
-            handle_input(state.input.borrow(), &mut pos, &mut state.cfg);
+        let (key_pressed, action, menu) = handle_input(
+            state.input.borrow(),
+            &state.menu_input(),
+            &mut pos,
+            &mut state.cfg,
+        );
+
+        // the device of player 1 keeps the menu up as well as the keyboard
+        if key_pressed {
+            idle_since = now();
+        }
 
         if let Some(action) = action {
             break action;
@@ -93,11 +117,21 @@ pub async fn main_menu(state: &mut State) -> Result<Action> {
 
         if let Some(menu) = menu {
             first_time = true;
-            fade_out(None).await;
+            fade_out().await;
 
             match menu {
+                Menu::Controls => controls_menu(state, pal).await?,
                 Menu::Define => define_menu(state, pal).await?,
+                Menu::Recs => {
+                    recs_menu(state, pal).await?;
+
+                    if Track::parse(&state.cfg.code).is_ok() {
+                        code = state.cfg.code.clone();
+                    }
+                }
             }
+
+            idle_since = now();
         }
 
         if first_time || key_pressed {
@@ -127,12 +161,24 @@ pub async fn main_menu(state: &mut State) -> Result<Action> {
             if first_time {
                 first_time = false;
 
-                fade_in(None).await;
+                fade_in().await;
             }
         }
     };
 
-    fade_out(None).await;
+    if let Err(e) = Track::parse(&state.cfg.code) {
+        eprintln!("{e} Keeping '{code}'.");
+        state.cfg.code = code;
+    }
+
+    // a failed save costs the changes only, not the game
+    if let Some(path) = &state.settings {
+        if let Err(e) = settings::save(&state.cfg, path) {
+            eprintln!("{e:?}");
+        }
+    }
+
+    fade_out().await;
 
     Ok(action)
 }
@@ -157,11 +203,14 @@ macro_rules! switch_option {
 }
 
 enum Menu {
+    Controls,
     Define,
+    Recs,
 }
 
 fn handle_input(
     input: Ref<InputHelper>,
+    menu_input: &MenuInput,
     pos: &mut Position,
     cfg: &mut Config,
 ) -> (bool, Option<Action>, Option<Menu>) {
@@ -169,31 +218,31 @@ fn handle_input(
     let mut action = None;
     let mut menu = None;
 
-    if input.key_pressed(NamedKey::ArrowUp) && pos.row > 0 {
+    if menu_input.pressed(&input, Control::Accelerate) && pos.row > 0 {
         pos.row -= 1;
         pos.editor = false;
         key_pressed = true;
     }
 
-    if input.key_pressed(NamedKey::ArrowDown) && pos.row < 4 {
+    if menu_input.pressed(&input, Control::Brake) && pos.row < 4 {
         pos.row += 1;
         pos.editor = false;
         key_pressed = true;
     }
 
-    if input.key_pressed(NamedKey::ArrowLeft) && pos.col > 0 {
+    if menu_input.pressed(&input, Control::SteerLeft) && pos.col > 0 {
         pos.col -= 1;
         pos.editor = false;
         key_pressed = true;
     }
 
-    if input.key_pressed(NamedKey::ArrowRight) && pos.col < 2 {
+    if menu_input.pressed(&input, Control::SteerRight) && pos.col < 2 {
         pos.col += 1;
         pos.editor = false;
         key_pressed = true;
     }
 
-    if input.key_pressed(NamedKey::Escape) {
+    if menu_input.pressed(&input, Control::Back) {
         if pos.editor {
             pos.editor = false;
         } else {
@@ -203,7 +252,7 @@ fn handle_input(
         key_pressed = true;
     }
 
-    if input.key_pressed(NamedKey::Enter) {
+    if menu_input.pressed(&input, Control::Confirm) {
         match (pos.row, pos.col) {
             (0, 0) => {
                 pos.editor = !pos.editor;
@@ -249,7 +298,7 @@ fn handle_input(
                 key_pressed = true;
             }
             (3, 0) => {
-                // Controls
+                menu = Some(Menu::Controls);
                 key_pressed = true;
             }
             (3, 1) => {
@@ -261,7 +310,7 @@ fn handle_input(
                 key_pressed = true;
             }
             (4, 0) => {
-                // RECS
+                menu = Some(Menu::Recs);
                 key_pressed = true;
             }
             (4, 1) => {
@@ -1,8 +1,12 @@
+mod controls;
 mod define;
 mod main;
+mod recs;
 
+pub use controls::*;
 pub use define::*;
 pub use main::*;
+pub use recs::*;
 
 use crate::graphics::Size;
 
@@ -0,0 +1,243 @@
+use anyhow::Result;
+use std::cell::Ref;
+
+use crate::{
+    engine::State,
+    game::recs::{Scenery, Track, MAX_VALUE},
+    graphics::{
+        font::{Font, CHAR_SET_04},
+        palette_color, Frame, Point, Size,
+    },
+    input::{Action, InputHelper, MenuInput},
+    screen::{fade_in, fade_out, screen, screen_at},
+    task::tick,
+};
+
+const TITLE_POS: (u32, u32) = (146, 8);
+const ROW_TOP: u32 = 30; // y of the first slider
+const ROW_HEIGHT: u32 = 15;
+const COL_NAME: u32 = 16; // x of the slider names
+const COL_VALUE: u32 = 104; // x of the values
+const COL_BAR: u32 = 124; // x of the slider bars
+const BAR_HEIGHT: u32 = 7;
+const BAR_SCALE: u32 = 3; // pixels per step of a value
+const ROW_FRAME_SIZE: Size = Size::wh(204, 17);
+const CODE_POS: (u32, u32) = (118, 176);
+
+// the top-down map of the road
+const PREVIEW_POS: (u32, u32) = (216, 36);
+const PREVIEW_SIZE: u32 = 96;
+
+const ROWS: [&str; 9] = [
+    "CURVES",
+    "HILLS",
+    "STEEPNESS",
+    "SCATTER",
+    "OBSTACLES",
+    "DIFFICULTY",
+    "LENGTH",
+    "VARIATION",
+    "SCENERY",
+];
+
+const SCENERY_ROW: u8 = 8;
+
+const BAR_COLOR: u8 = 0x20; // the red of the frames
+const ROAD_COLOR: u32 = 0xffff_ffff;
+
+#[derive(Default)]
+struct Position {
+    row: u8,
+}
+
+/// Edits the RECS code of the options, every change shows the road it makes. The code is kept
+/// when the editor is left by confirming.
+pub async fn recs_menu(state: &mut State, pal: &[u8]) -> Result<()> {
+    let font = Font::from(CHAR_SET_04, state.arc.get("C04")?);
+    let frame = Frame::new(ROW_FRAME_SIZE);
+
+    let mut track = Track::parse_or_default(&state.cfg.code);
+
+    let mut first_time = true;
+    let mut pos = Position::default();
+
+    loop {
+        tick().await;
+
+        let (key_pressed, exit) = handle_input(
+            state.input.borrow(),
+            &state.menu_input(),
+            &mut pos,
+            &mut track,
+        );
+
+        match exit {
+            Some(true) => {
+                state.cfg.code = track.code();
+                break;
+            }
+            Some(false) => break,
+            None => {}
+        }
+
+        if first_time || key_pressed {
+            screen().fill(0xff00_0000);
+
+            font.print(screen_at(TITLE_POS), "RECS", pal);
+
+            for (row, name) in (0..).zip(ROWS) {
+                let y = ROW_TOP + row * ROW_HEIGHT;
+
+                font.print(screen_at((COL_NAME, y)), name, pal);
+
+                if row == SCENERY_ROW as u32 {
+                    font.print(screen_at((COL_VALUE, y)), track.scenery.name(), pal);
+                } else {
+                    let value = *value_mut(&mut track, row as u8);
+
+                    font.print(screen_at((COL_VALUE, y)), &value.to_string(), pal);
+                    draw_bar((COL_BAR, y + 1), value as u32 * BAR_SCALE, pal);
+                }
+
+                if pos.row as u32 == row {
+                    frame.draw(screen_at((COL_NAME - 7, y - 5)), pal);
+                }
+            }
+
+            draw_preview(&track);
+
+            font.print(screen_at(CODE_POS), &track.code(), pal);
+
+            if first_time {
+                first_time = false;
+
+                fade_in().await;
+            }
+        }
+    }
+
+    fade_out().await;
+
+    Ok(())
+}
+
+/// Returns the value of a slider row, every row but the scenery one has a value.
+fn value_mut(track: &mut Track, row: u8) -> &mut u8 {
+    match row {
+        0 => &mut track.curves,
+        1 => &mut track.hills,
+        2 => &mut track.steepness,
+        3 => &mut track.scatter,
+        4 => &mut track.obstacles,
+        5 => &mut track.difficulty,
+        6 => &mut track.length,
+        7 => &mut track.variation,
+        _ => unreachable!("row {row} has no value"),
+    }
+}
+
+fn draw_bar((x, y): (u32, u32), width: u32, pal: &[u8]) {
+    let color = palette_color(pal, BAR_COLOR);
+
+    let buffer = screen();
+
+    for yy in y..y + BAR_HEIGHT {
+        for xx in x..x + width {
+            buffer[Point::xy(xx, yy).index()] = color;
+        }
+    }
+}
+
+/// Draws the road from above, scaled to fit the preview box.
+fn draw_preview(track: &Track) {
+    let (mut x, mut y, mut heading) = (0.0_f32, 0.0_f32, 0.0_f32);
+
+    let points = track
+        .segments()
+        .into_iter()
+        .map(|segment| {
+            heading += segment.curve * 0.02;
+            x += heading.sin();
+            y -= heading.cos();
+
+            (x, y)
+        })
+        .collect::<Vec<_>>();
+
+    let (min_x, max_x, min_y, max_y) = points.iter().fold(
+        (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
+        |(min_x, max_x, min_y, max_y), (x, y)| {
+            (min_x.min(*x), max_x.max(*x), min_y.min(*y), max_y.max(*y))
+        },
+    );
+
+    let scale = (PREVIEW_SIZE - 1) as f32 / (max_x - min_x).max(max_y - min_y).max(1.0);
+
+    // centre the shorter side
+    let offset_x = (PREVIEW_SIZE as f32 - (max_x - min_x) * scale) / 2.0;
+    let offset_y = (PREVIEW_SIZE as f32 - (max_y - min_y) * scale) / 2.0;
+
+    let buffer = screen();
+
+    for (x, y) in points {
+        let x = PREVIEW_POS.0 + (offset_x + (x - min_x) * scale) as u32;
+        let y = PREVIEW_POS.1 + (offset_y + (y - min_y) * scale) as u32;
+
+        buffer[Point::xy(x, y).index()] = ROAD_COLOR;
+    }
+}
+
+/// Returns whether the screen has to be redrawn, and `Some` when the editor is left: `true` to
+/// keep the code.
+fn handle_input(
+    input: Ref<InputHelper>,
+    menu: &MenuInput,
+    pos: &mut Position,
+    track: &mut Track,
+) -> (bool, Option<bool>) {
+    let mut key_pressed = false;
+
+    if menu.pressed(&input, Action::Accelerate) && pos.row > 0 {
+        pos.row -= 1;
+        key_pressed = true;
+    }
+
+    if menu.pressed(&input, Action::Brake) && (pos.row as usize) < ROWS.len() - 1 {
+        pos.row += 1;
+        key_pressed = true;
+    }
+
+    let step: i32 = match (
+        menu.pressed(&input, Action::SteerLeft),
+        menu.pressed(&input, Action::SteerRight),
+    ) {
+        (true, false) => -1,
+        (false, true) => 1,
+        _ => 0,
+    };
+
+    if step != 0 {
+        if pos.row == SCENERY_ROW {
+            let count = Scenery::ALL.len() as i32;
+            let index = (track.scenery as i32 + step).rem_euclid(count);
+
+            track.scenery = Scenery::ALL[index as usize];
+        } else {
+            let value = value_mut(track, pos.row);
+
+            *value = (*value as i32 + step).clamp(0, MAX_VALUE as i32) as u8;
+        }
+
+        key_pressed = true;
+    }
+
+    if menu.pressed(&input, Action::Back) {
+        return (false, Some(false));
+    }
+
+    if menu.pressed(&input, Action::Confirm) {
+        return (false, Some(true));
+    }
+
+    (key_pressed, None)
+}
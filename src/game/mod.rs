@@ -1,69 +1,125 @@
 use anyhow::Result;
+use std::path::Path;
 
-use crate::{engine::State, screen::fade_out};
+use crate::{engine::State, game::options::Model, DEMOS_DIR};
 
+mod demo;
 mod intro;
 mod menu;
 mod screen;
 
+pub mod code_wheel;
 pub mod options;
+pub mod race;
+pub mod recs;
+pub mod scores;
+pub mod settings;
 
+use demo::*;
 use intro::*;
+
+pub use intro::show_lotus_logo;
 use menu::*;
+use race::race;
 use screen::*;
 
+/// The screens the game can start at, the ones before are skipped.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub enum Screen {
+    #[default]
+    Protection,
+    Intro,
+    Demo,
+    Menu,
+    SelectModel,
+    AudioTuner,
+}
+
+impl Screen {
+    pub const ALL: [(Screen, &'static str); 6] = [
+        (Screen::Protection, "protection"),
+        (Screen::Intro, "intro"),
+        (Screen::Demo, "demo"),
+        (Screen::Menu, "menu"),
+        (Screen::SelectModel, "select-model"),
+        (Screen::AudioTuner, "audio-tuner"),
+    ];
+}
+
+/// How the game starts.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Launch {
+    pub screen: Screen,
+    pub skip_protection: bool,
+    pub skip_intro: bool,
+}
+
 pub async fn main(mut state: State) -> Result<()> {
-    protection(&mut state).await?;
+    let launch = state.launch;
+
+    let skip_protection = launch.skip_protection || state.cfg.skip_protection;
 
-    let mut ok = true; // ok = false when an async operation has been cancelled
-    ok = ok && show_gremlin(&mut state).await?;
-    ok = ok && show_magnetic_fields(&mut state).await?;
-    ok = ok && show_credits(&mut state).await?;
-    ok = ok && show_lotus_logo(&mut state).await?;
-    ok = ok && show_magazine(&mut state).await?;
+    if launch.screen <= Screen::Protection && !skip_protection && !protection(&mut state).await? {
+        return Ok(());
+    }
 
-    if !ok {
-        fade_out(None).await;
+    if launch.screen <= Screen::Intro && !launch.skip_intro {
+        show_intro(&mut state).await?;
     }
 
-    let mut play_demo = true;
+    let demos = load_demos(Path::new(DEMOS_DIR));
+    let mut play_demo = launch.screen <= Screen::Demo;
+    let mut screen = launch.screen.max(Screen::Menu);
 
     loop {
         if play_demo {
-            /*loop { // for each demo
-                ok = show_lotus_logo(&mut state).await?;
-                // DEMO
-                // SCORE
-
-                if !ok {
-                    state.screen.fade_out(None).await;
-                    break;
-                }
-            }*/
+            attract_mode(&mut state, &demos).await?;
         }
 
         play_demo = false;
 
-        match main_menu(&mut state).await? {
-            Action::Start => (),
-            Action::Exit => break,
+        if screen == Screen::Menu {
+            match main_menu(&mut state).await? {
+                Action::Start => (),
+                Action::Demo => {
+                    play_demo = true;
+                    continue;
+                }
+                Action::Exit => break,
+            }
         }
 
-        let _model = match select_model(&mut state).await? {
-            Some(model) => model,
-            None => continue,
-        };
-
-        // if SOUND != OFF:
-        let _track = match audio_tuner(&mut state).await? {
-            Some(track) => track,
-            None => continue,
-        };
-
-        // play_demo = true;
+        // backed out or raced, the menu comes next either way
+        if state.record_demo {
+            record_demo(&mut state, screen).await?;
+        } else {
+            play(&mut state, screen).await?;
+        }
 
-        break;
+        // a screen given at the start is shown once, the menu follows as usual
+        screen = Screen::Menu;
     }
 
     Ok(())
 }
+
+/// Plays from the screen after the main menu on, returns `false` if the player has backed out.
+pub async fn play(state: &mut State, from: Screen) -> Result<bool> {
+    // the default car when the game has started past the selection
+    let model = if from <= Screen::SelectModel {
+        match select_model(state).await? {
+            Some(model) => model,
+            None => return Ok(false),
+        }
+    } else {
+        Model::default()
+    };
+
+    // if SOUND != OFF:
+    let _track = match audio_tuner(state).await? {
+        Some(track) => track,
+        None => return Ok(false),
+    };
+
+    race(state, model).await
+}
@@ -1,4 +1,12 @@
-#[derive(Clone, PartialEq, Eq)]
+use anyhow::{anyhow, ensure, Result};
+use std::fmt;
+
+use crate::{
+    game::{race::environment::Effects, recs::Track},
+    input::{key_name, parse_key, Action, Controls},
+};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Config {
     pub p1_name: String,
     pub p1_trans: Transmission,
@@ -10,6 +18,11 @@ pub struct Config {
     pub course: Course,
     pub players_num: u8,
     pub code: String,
+    /// Skips the code wheel at the start, for the owners tired of turning it.
+    pub skip_protection: bool,
+    /// The weather and the light of the races, `None` for the ones of the scenery.
+    pub effects: Option<Effects>,
+    pub controls: Controls,
 }
 
 impl Config {
@@ -25,29 +38,174 @@ impl Config {
             course: Course::T1,
             players_num: 1,
             code: "VBJD D   -99".to_string(),
+            skip_protection: false,
+            effects: None,
+            controls: Controls::default(),
+        }
+    }
+
+    /// Returns the options as `key=value` pairs, the format of the settings file and the
+    /// recordings.
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries = vec![
+            ("p1_name".to_string(), self.p1_name.clone()),
+            ("p1_trans".to_string(), self.p1_trans.name().to_string()),
+            ("p1_accel".to_string(), self.p1_accel.name().to_string()),
+            ("p2_name".to_string(), self.p2_name.clone()),
+            ("p2_trans".to_string(), self.p2_trans.name().to_string()),
+            ("p2_accel".to_string(), self.p2_accel.name().to_string()),
+            ("race".to_string(), self.race.name().to_string()),
+            ("course".to_string(), self.course.name().to_string()),
+            ("players_num".to_string(), self.players_num.to_string()),
+            ("code".to_string(), self.code.clone()),
+            (
+                "skip_protection".to_string(),
+                self.skip_protection.to_string(),
+            ),
+            (
+                "effects".to_string(),
+                self.effects.map_or("scenery".to_string(), |x| x.name()),
+            ),
+        ];
+
+        for (player, bindings) in self.controls.players.iter().enumerate() {
+            for action in Action::ALL {
+                entries.push((
+                    control_key(player, action),
+                    key_name(bindings.get(action)).unwrap_or_default(),
+                ));
+            }
+        }
+
+        entries
+    }
+
+    /// Sets the option by its key as returned by [`Config::entries`]. Codes are checked and kept
+    /// in upper case.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let invalid = || anyhow!("Invalid value '{value}' of option '{key}'!");
+
+        match key {
+            "p1_name" => self.p1_name = player_name(value).ok_or_else(invalid)?,
+            "p1_trans" => self.p1_trans = Transmission::from_name(value).ok_or_else(invalid)?,
+            "p1_accel" => self.p1_accel = Acceleration::from_name(value).ok_or_else(invalid)?,
+            "p2_name" => self.p2_name = player_name(value).ok_or_else(invalid)?,
+            "p2_trans" => self.p2_trans = Transmission::from_name(value).ok_or_else(invalid)?,
+            "p2_accel" => self.p2_accel = Acceleration::from_name(value).ok_or_else(invalid)?,
+            "race" => self.race = Race::from_name(value).ok_or_else(invalid)?,
+            "course" => self.course = Course::from_name(value).ok_or_else(invalid)?,
+            "players_num" => {
+                let players_num = value.parse().map_err(|_| invalid())?;
+                ensure!((1..=2).contains(&players_num), invalid());
+
+                self.players_num = players_num;
+            }
+            "code" => self.code = Track::parse(value)?.code(),
+            "skip_protection" => self.skip_protection = value.parse().map_err(|_| invalid())?,
+            "effects" => {
+                self.effects = match value {
+                    "scenery" => None,
+                    _ => Some(Effects::from_name(value).ok_or_else(invalid)?),
+                }
+            }
+            _ => {
+                let (player, action) = (0..2)
+                    .flat_map(|p| Action::ALL.map(|a| (p, a)))
+                    .find(|(p, a)| control_key(*p, *a) == key)
+                    .ok_or_else(|| anyhow!("Unknown option '{key}'!"))?;
+
+                let key = parse_key(value).ok_or_else(invalid)?;
+
+                self.controls.set(player, action, key);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses the `key=value` lines written from [`Config::entries`] over the defaults, the first
+    /// bad line is an error.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut cfg = Self::new();
+
+        for (_, entry) in key_values(text) {
+            let (key, value) = entry?;
+
+            cfg.set(key, value)?;
+        }
+
+        Ok(cfg)
+    }
+}
+
+/// Splits the `key=value` lines of a settings file or a recording into their line numbers from 1,
+/// the keys and the values. Keys are trimmed, values are taken verbatim, as codes may start with
+/// spaces. Empty lines and lines starting with `#` are skipped.
+pub fn key_values(text: &str) -> impl Iterator<Item = (usize, Result<(&str, &str)>)> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(n, line)| {
+            let entry = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value))
+                .ok_or_else(|| anyhow!("Invalid line '{line}'!"));
+
+            (n + 1, entry)
+        })
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in self.entries() {
+            writeln!(f, "{key}={value}")?;
         }
+
+        Ok(())
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+fn control_key(player: usize, action: Action) -> String {
+    format!(
+        "p{}_{}",
+        player + 1,
+        action.name().to_lowercase().replace(' ', "_")
+    )
+}
+
+fn player_name(value: &str) -> Option<String> {
+    (value.len() <= 12
+        && value
+            .chars()
+            .all(|c| c == ' ' || c.is_ascii_uppercase() || c.is_ascii_digit()))
+    .then(|| value.to_string())
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Transmission {
     Manual = 0,
     Automatic = 1,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Acceleration {
     Button = 0,
     Joystick = 1,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Race {
     TimeLimit = 0,
     Competition = 1,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Course {
     T1 = 0,
     T2 = 1,
@@ -56,19 +214,45 @@ pub enum Course {
     Unknown = 4,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+/// Names the values of an option enum, for [`Config::entries`] and [`Config::set`].
+macro_rules! option_names {
+    ($ty:ident { $($variant:ident => $name:literal),+ $(,)? }) => {
+        impl $ty {
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)+
+                }
+            }
+
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $($name => Some(Self::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+option_names!(Transmission { Manual => "manual", Automatic => "automatic" });
+option_names!(Acceleration { Button => "button", Joystick => "joystick" });
+option_names!(Race { TimeLimit => "time_limit", Competition => "competition" });
+option_names!(Course {
+    T1 => "t1",
+    T2 => "t2",
+    T3 => "t3",
+    Circular => "circular",
+    Unknown => "unknown",
+});
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
 pub enum Model {
+    #[default]
     Esprit = 0,
     Elan = 1,
     M200 = 2,
 }
 
-impl Default for Model {
-    fn default() -> Self {
-        Self::Esprit
-    }
-}
-
 impl Model {
     pub fn next(&self) -> Self {
         match self {
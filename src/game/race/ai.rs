@@ -0,0 +1,205 @@
+use super::{
+    physics::{Car, CAR_LENGTH},
+    road::Road,
+};
+use crate::{
+    game::{
+        options::{Model, Transmission},
+        recs::{Track, MAX_VALUE},
+    },
+    input::DriverInput,
+    rng::Rng,
+};
+
+/// The segments an opponent looks ahead for the curves.
+const LOOK_AHEAD: usize = 20;
+
+/// How far the racing line cuts into a curve, in half widths of the road per unit of the curve.
+const LINE_CUT: f32 = 0.2;
+const MAX_LINE: f32 = 0.7;
+
+/// How much a curve ahead slows the opponents down, per unit of the curve.
+const CURVE_BRAKING: f32 = 0.08;
+
+/// The distance to the player beyond which the opponents ease off or catch up, in world units,
+/// and how much they do at most.
+const RUBBER_DISTANCE: f32 = 4000.0;
+const MAX_RUBBER: f32 = 0.12;
+
+/// The distance at which an opponent reacts to a car ahead of it, and how far it pulls out to
+/// pass.
+const OVERTAKE_DISTANCE: f32 = 6.0 * CAR_LENGTH;
+const OVERTAKE_OFFSET: f32 = 0.5;
+
+/// The distance at which an opponent blocks the player behind it.
+const BLOCK_DISTANCE: f32 = 4.0 * CAR_LENGTH;
+
+const STEER_GAIN: f32 = 4.0;
+
+/// The spacing of the starting grid, the player starts at the back.
+const GRID_SPACING: f32 = 2.0 * CAR_LENGTH;
+
+/// How an opponent drives.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Skill {
+    /// The share of the top speed it aims for on the straights.
+    pub pace: f32,
+    /// How closely it keeps to the racing line, `0.0..=1.0`.
+    pub line: f32,
+    /// How willing it is to block the player, `0.0..=1.0`.
+    pub aggression: f32,
+}
+
+impl Skill {
+    /// The skill of the opponent at the grid position `rank`, the ones at the front are the
+    /// better ones. The RECS difficulty raises them all.
+    pub fn new(difficulty: u8, rank: usize, rng: &mut Rng) -> Self {
+        let level = difficulty.min(MAX_VALUE) as f32 / MAX_VALUE as f32;
+
+        Self {
+            pace: 0.72 + 0.22 * level - 0.01 * rank as f32 + 0.02 * rng.next_f32(),
+            line: 0.5 + 0.5 * level * rng.next_f32().max(0.5),
+            aggression: 0.2 + 0.6 * level * rng.next_f32(),
+        }
+    }
+}
+
+/// A computer driven car.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Opponent {
+    pub car: Car,
+    pub skill: Skill,
+    /// The side it takes to pass the car ahead, kept until it's passed.
+    passing: Option<f32>,
+}
+
+impl Opponent {
+    pub fn new(car: Car, skill: Skill) -> Self {
+        Self {
+            car,
+            skill,
+            passing: None,
+        }
+    }
+
+    /// Decides the input of the tick. `ahead` is the car in front of it, if any, and `player` is
+    /// the one it races against.
+    pub fn drive(&mut self, road: &Road, ahead: Option<&Car>, player: &Car) -> DriverInput {
+        let handling = self.car.handling();
+
+        let curve = (0..LOOK_AHEAD)
+            .map(|n| {
+                road.segments()[(road.segment_at(self.car.z) + n) % road.segments().len()].curve
+            })
+            .sum::<f32>()
+            / LOOK_AHEAD as f32;
+
+        // the inside of the curve ahead
+        let mut target_x = (curve * LINE_CUT * self.skill.line).clamp(-MAX_LINE, MAX_LINE);
+
+        let mut target_speed =
+            self.skill.pace * handling.top_speed * (1.0 - curve.abs() * CURVE_BRAKING).max(0.3);
+
+        // ease off ahead of the player, catch up behind
+        let gap = self.car.z - player.z;
+        target_speed *= 1.0 - (gap / RUBBER_DISTANCE).clamp(-1.0, 1.0) * MAX_RUBBER;
+
+        match ahead {
+            Some(ahead)
+                if ahead.z - self.car.z < OVERTAKE_DISTANCE && ahead.speed < target_speed =>
+            {
+                let side = *self
+                    .passing
+                    .get_or_insert(if ahead.x > 0.0 { -1.0 } else { 1.0 });
+
+                target_x = (ahead.x + side * OVERTAKE_OFFSET).clamp(-MAX_LINE, MAX_LINE);
+            }
+            _ => self.passing = None,
+        }
+
+        if gap > 0.0 && gap < BLOCK_DISTANCE && self.passing.is_none() {
+            target_x += (player.x - target_x) * self.skill.aggression;
+        }
+
+        DriverInput {
+            steer: ((target_x - self.car.x) * STEER_GAIN).clamp(-1.0, 1.0),
+            throttle: (self.car.speed < target_speed) as u8 as f32,
+            brake: (self.car.speed > target_speed * 1.1) as u8 as f32,
+            ..DriverInput::default()
+        }
+    }
+}
+
+/// The opponents of a competition race.
+pub struct Field {
+    pub opponents: Vec<Opponent>,
+}
+
+impl Field {
+    /// Lines up `count` opponents on the grid ahead of the player, who starts at the back. The
+    /// same seed gives the same field.
+    pub fn new(track: &Track, count: usize, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+
+        let opponents = (0..count)
+            .map(|rank| {
+                let model = [Model::Esprit, Model::Elan, Model::M200][rng.below(3) as usize];
+
+                let mut car = Car::new(model, Transmission::Automatic);
+                car.z = (count - rank) as f32 * GRID_SPACING;
+                car.x = if rank % 2 == 0 { -0.4 } else { 0.4 };
+
+                Opponent::new(car, Skill::new(track.difficulty, rank, &mut rng))
+            })
+            .collect();
+
+        Self { opponents }
+    }
+
+    /// Advances the opponents by a tick and lets them bump into each other and the players. Each
+    /// opponent races the player nearest to it.
+    pub fn update(&mut self, road: &Road, players: &mut [Car]) {
+        let cars = self
+            .opponents
+            .iter()
+            .map(|x| x.car.clone())
+            .chain(players.iter().cloned())
+            .collect::<Vec<_>>();
+
+        for opponent in &mut self.opponents {
+            let ahead = cars
+                .iter()
+                .filter(|x| x.z > opponent.car.z)
+                .min_by(|a, b| a.z.total_cmp(&b.z));
+
+            let Some(player) = players.iter().min_by(|a, b| {
+                (a.z - opponent.car.z)
+                    .abs()
+                    .total_cmp(&(b.z - opponent.car.z).abs())
+            }) else {
+                continue;
+            };
+
+            let input = opponent.drive(road, ahead, player);
+            opponent.car.update(&input, road);
+        }
+
+        for i in 0..self.opponents.len() {
+            let (left, right) = self.opponents.split_at_mut(i + 1);
+            let car = &mut left[i].car;
+
+            for player in players.iter_mut() {
+                car.collide(player);
+            }
+
+            for other in right {
+                car.collide(&mut other.car);
+            }
+        }
+    }
+
+    /// The position of the player in the race, from 1.
+    pub fn position(&self, player: &Car) -> usize {
+        1 + self.opponents.iter().filter(|x| x.car.z > player.z).count()
+    }
+}
@@ -0,0 +1,299 @@
+use super::road::{Viewport, CAMERA_DEPTH, DRAW_DISTANCE, SEGMENT_LENGTH};
+use crate::{
+    game::recs::Scenery,
+    graphics::{blend, Point},
+    rng::Rng,
+};
+
+/// The colours of the particles, kept out of the palette so the sprites keep every entry of it.
+pub const RAIN_COLOR: u32 = 0xffa0_b8e0;
+pub const SNOW_COLOR: u32 = 0xfffc_fcfc;
+
+/// The colour of the fog, in 6-bit VGA components.
+const FOG_COLOR: [u8; 3] = [40, 42, 44];
+
+/// The palettes from the clear one to the one lost in the fog.
+const FOG_LEVELS: usize = 8;
+
+/// The share of the draw distance beyond which everything is lost in the fog.
+const FOG_REACH: f32 = 0.6;
+
+/// The share of the grip and the brakes left on the wet and the icy roads.
+const WET_TRACTION: f32 = 0.75;
+const ICY_TRACTION: f32 = 0.55;
+
+const RAIN_DROPS: usize = 120;
+const SNOW_FLAKES: usize = 80;
+
+/// How much of the light is gone outside the headlights.
+const NIGHT_DARKNESS: f64 = 0.8;
+
+/// The half widths of the light cone at the bottom and at the middle of the viewport, as shares
+/// of its width.
+const BEAM_NEAR: f32 = 0.4;
+const BEAM_FAR: f32 = 0.08;
+
+/// The weather and the light of a race, each one can be chosen on its own with the `effects`
+/// option.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Effects {
+    pub fog: bool,
+    pub rain: bool,
+    pub snow: bool,
+    pub night: bool,
+}
+
+impl Effects {
+    /// The effects the scenery comes with.
+    pub fn of(scenery: Scenery) -> Self {
+        let none = Self::default();
+
+        match scenery {
+            Scenery::Fog => Self { fog: true, ..none },
+            Scenery::Storm => Self {
+                rain: true,
+                fog: true,
+                ..none
+            },
+            Scenery::Snow => Self { snow: true, ..none },
+            Scenery::Night => Self {
+                night: true,
+                ..none
+            },
+            _ => none,
+        }
+    }
+
+    /// Returns the effects separated by commas, `none` if there are none.
+    pub fn name(&self) -> String {
+        let names = [
+            (self.fog, "fog"),
+            (self.rain, "rain"),
+            (self.snow, "snow"),
+            (self.night, "night"),
+        ]
+        .into_iter()
+        .filter_map(|(on, name)| on.then_some(name))
+        .collect::<Vec<_>>();
+
+        match names.is_empty() {
+            true => "none".to_string(),
+            false => names.join(","),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mut effects = Self::default();
+
+        if name == "none" {
+            return Some(effects);
+        }
+
+        for name in name.split(',') {
+            match name.trim() {
+                "fog" => effects.fog = true,
+                "rain" => effects.rain = true,
+                "snow" => effects.snow = true,
+                "night" => effects.night = true,
+                _ => return None,
+            }
+        }
+
+        Some(effects)
+    }
+
+    /// The share of the grip and the brakes the road gives.
+    pub fn traction(&self) -> f32 {
+        match (self.snow, self.rain) {
+            (true, _) => ICY_TRACTION,
+            (false, true) => WET_TRACTION,
+            _ => 1.0,
+        }
+    }
+}
+
+/// The palettes of the distances in the fog, blended from the race one to the fog colour.
+pub struct Fog {
+    levels: Vec<Vec<u8>>,
+}
+
+impl Fog {
+    pub fn new(palette: &[u8]) -> Self {
+        let levels = (0..FOG_LEVELS)
+            .map(|level| {
+                let t = level as f32 / (FOG_LEVELS - 1) as f32;
+
+                palette
+                    .iter()
+                    .enumerate()
+                    .map(|(i, x)| {
+                        let fog = FOG_COLOR[i % 3] as f32;
+
+                        (*x as f32 + (fog - *x as f32) * t).round() as u8
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self { levels }
+    }
+
+    /// Returns the palette of things `z` world units from the camera.
+    pub fn palette(&self, z: f32) -> &[u8] {
+        let reach = DRAW_DISTANCE as f32 * SEGMENT_LENGTH * FOG_REACH;
+        let level = (z / reach * (FOG_LEVELS - 1) as f32).round() as usize;
+
+        &self.levels[level.min(FOG_LEVELS - 1)]
+    }
+
+    /// Returns the palette of a billboard of the depth, see
+    /// [`Billboard::depth`](super::scenery::Billboard::depth).
+    pub fn billboard_palette(&self, depth: f32) -> &[u8] {
+        self.palette(depth * CAMERA_DEPTH)
+    }
+
+    /// The palette of the sky, which is all fog.
+    pub fn sky(&self) -> &[u8] {
+        &self.levels[FOG_LEVELS - 1]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Particle {
+    /// The position as shares of the viewport.
+    x: f32,
+    y: f32,
+    /// The fall per tick as a share of the viewport height.
+    speed: f32,
+}
+
+/// The rain drops or the snow flakes falling in front of the camera. They are kept as shares of
+/// the viewport, so the same ones serve both halves of the split screen.
+pub struct Particles {
+    snow: bool,
+    particles: Vec<Particle>,
+    rng: Rng,
+    ticks: u32,
+}
+
+impl Particles {
+    pub fn rain(seed: u64) -> Self {
+        Self::new(false, RAIN_DROPS, seed)
+    }
+
+    pub fn snow(seed: u64) -> Self {
+        Self::new(true, SNOW_FLAKES, seed)
+    }
+
+    fn new(snow: bool, count: usize, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+
+        let particles = (0..count)
+            .map(|_| Particle {
+                x: rng.next_f32(),
+                y: rng.next_f32(),
+                speed: match snow {
+                    true => 0.004 + rng.next_f32() * 0.004,
+                    false => 0.03 + rng.next_f32() * 0.02,
+                },
+            })
+            .collect();
+
+        Self {
+            snow,
+            particles,
+            rng,
+            ticks: 0,
+        }
+    }
+
+    /// Lets the particles fall by a tick, the ones which have left the view come back at the top.
+    pub fn update(&mut self) {
+        self.ticks += 1;
+
+        for (i, particle) in self.particles.iter_mut().enumerate() {
+            particle.y += particle.speed;
+
+            particle.x += match self.snow {
+                // the flakes sway, the drops are blown aslant
+                true => ((self.ticks + i as u32 * 7) as f32 * 0.05).sin() * 0.001,
+                false => particle.speed * 0.2,
+            };
+
+            if particle.y >= 1.0 {
+                particle.y -= 1.0;
+                particle.x = self.rng.next_f32();
+            }
+
+            particle.x = particle.x.rem_euclid(1.0);
+        }
+    }
+
+    pub fn draw(&self, buffer: &mut [u32], viewport: &Viewport) {
+        let (color, alpha) = match self.snow {
+            true => (SNOW_COLOR, 0.9),
+            false => (RAIN_COLOR, 0.5),
+        };
+
+        let width = viewport.width as f32;
+        let height = viewport.height as f32;
+
+        for particle in &self.particles {
+            let x = particle.x * width;
+            let y = particle.y * height;
+
+            // the drops streak down, the flakes are dots
+            let (size, length) = match self.snow {
+                true => (2.0, 2.0),
+                false => (1.0, particle.speed * height),
+            };
+
+            for dy in 0..length.ceil() as u32 {
+                let row = y as u32 + dy;
+
+                if row >= viewport.height {
+                    break;
+                }
+
+                let sx = (x + dy as f32 * 0.2) as u32;
+
+                for px in sx..(sx + size as u32).min(viewport.width) {
+                    let i = Point::xy(viewport.x + px, viewport.y + row).index();
+
+                    buffer[i] = blend(buffer[i], color, alpha);
+                }
+            }
+        }
+    }
+}
+
+/// Darkens the view but for the cone of the headlights, which reaches from the bottom of the
+/// viewport to its middle, the horizon of a flat road.
+pub fn draw_night(buffer: &mut [u32], viewport: &Viewport) {
+    const BLACK: u32 = 0xff00_0000;
+
+    let width = viewport.width as f32;
+    let horizon = viewport.height as f32 / 2.0;
+
+    for y in 0..viewport.height {
+        // 0 at the horizon, 1 at the bottom
+        let near = ((y as f32 - horizon) / horizon).clamp(0.0, 1.0);
+        let half = width * (BEAM_FAR + (BEAM_NEAR - BEAM_FAR) * near);
+
+        // the light gives out with the distance
+        let lit = near.sqrt() as f64;
+
+        for x in 0..viewport.width {
+            let inside = (x as f32 - width / 2.0).abs() < half && near > 0.0;
+
+            let darkness = match inside {
+                true => NIGHT_DARKNESS * (1.0 - lit),
+                false => NIGHT_DARKNESS,
+            };
+
+            let i = Point::xy(viewport.x + x, viewport.y + y).index();
+
+            buffer[i] = blend(buffer[i], BLACK, darkness);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::{ensure, Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::game::{recs::Track, settings::settings_path};
+
+/// The ticks between the samples of a trace.
+pub const SAMPLE_TICKS: u32 = 4;
+
+/// The share of the ghost car mixed over the road.
+pub const GHOST_ALPHA: f32 = 0.5;
+
+const GHOSTS_DIR: &str = "ghosts";
+const MAGIC: &[u8; 4] = b"LGH1";
+
+/// The offsets are kept in these parts of a half width of the road.
+const OFFSET_UNIT: f32 = 10_000.0;
+
+/// Returns the directory of the ghosts, which is kept next to the settings file.
+pub fn ghosts_dir() -> Option<PathBuf> {
+    settings_path().and_then(|x| Some(x.parent()?.join(GHOSTS_DIR)))
+}
+
+/// Returns the file of the ghost of the track, named by its RECS code.
+pub fn ghost_path(dir: &Path, track: &Track) -> PathBuf {
+    dir.join(format!("{}.ghost", track.code().replace(' ', "_")))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Sample {
+    /// World units from the start line.
+    z: u32,
+    /// The offset from the centre of the road in [`OFFSET_UNIT`]s.
+    x: i16,
+}
+
+/// Where a car has been during a lap, sampled every [`SAMPLE_TICKS`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Trace {
+    lap_ticks: u32,
+    samples: Vec<Sample>,
+}
+
+impl Trace {
+    pub fn lap_ticks(&self) -> u32 {
+        self.lap_ticks
+    }
+
+    /// Returns the distance from the start line and the offset of the car the ticks into the
+    /// lap, `None` once the lap is over.
+    pub fn position(&self, ticks: u32) -> Option<(f32, f32)> {
+        if ticks >= self.lap_ticks {
+            return None;
+        }
+
+        let i = (ticks / SAMPLE_TICKS) as usize;
+        let a = self.samples.get(i)?;
+        let b = self.samples.get(i + 1).unwrap_or(a);
+        let t = (ticks % SAMPLE_TICKS) as f32 / SAMPLE_TICKS as f32;
+
+        let z = a.z as f32 + (b.z as f32 - a.z as f32) * t;
+        let x = (a.x as f32 + (b.x as f32 - a.x as f32) * t) / OFFSET_UNIT;
+
+        Some((z, x))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+
+        bytes.extend(self.lap_ticks.to_le_bytes());
+        bytes.extend((self.samples.len() as u32).to_le_bytes());
+
+        for sample in &self.samples {
+            bytes.extend(sample.z.to_le_bytes());
+            bytes.extend(sample.x.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(bytes.starts_with(MAGIC), "Not a ghost!");
+
+        let word = |i: usize| -> Result<u32> {
+            let bytes = bytes.get(i..i + 4).context("The ghost is cut short!")?;
+            Ok(u32::from_le_bytes(bytes.try_into()?))
+        };
+
+        let lap_ticks = word(4)?;
+        let count = word(8)? as usize;
+
+        ensure!(bytes.len() == 12 + count * 6, "The ghost is cut short!");
+
+        let samples = bytes[12..]
+            .chunks_exact(6)
+            .map(|x| Sample {
+                z: u32::from_le_bytes([x[0], x[1], x[2], x[3]]),
+                x: i16::from_le_bytes([x[4], x[5]]),
+            })
+            .collect();
+
+        Ok(Self { lap_ticks, samples })
+    }
+}
+
+/// Samples the lap being driven.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct LapRecorder {
+    samples: Vec<Sample>,
+}
+
+impl LapRecorder {
+    /// Takes a sample if it's due, `z` is the distance from the start line.
+    pub fn record(&mut self, lap_ticks: u32, z: f32, x: f32) {
+        if lap_ticks.is_multiple_of(SAMPLE_TICKS) {
+            self.samples.push(Sample {
+                z: z.max(0.0) as u32,
+                x: (x * OFFSET_UNIT).round() as i16,
+            });
+        }
+    }
+
+    /// Ends the lap, which has taken the ticks, and starts over.
+    pub fn finish(&mut self, lap_ticks: u32) -> Trace {
+        Trace {
+            lap_ticks,
+            samples: std::mem::take(&mut self.samples),
+        }
+    }
+}
+
+/// Loads the ghost of the track, `None` if there is none yet or it's broken.
+pub fn load(dir: &Path, track: &Track) -> Option<Trace> {
+    let path = ghost_path(dir, track);
+    let bytes = fs::read(&path).ok()?;
+
+    Trace::from_bytes(&bytes)
+        .with_context(|| format!("Failed to parse '{}'!", path.display()))
+        .map_err(|e| eprintln!("{e:?}"))
+        .ok()
+}
+
+pub fn save(dir: &Path, track: &Track, trace: &Trace) -> Result<()> {
+    let path = ghost_path(dir, track);
+
+    fs::create_dir_all(dir)?;
+    fs::write(&path, trace.to_bytes())
+        .with_context(|| format!("Failed to write '{}'!", path.display()))
+}
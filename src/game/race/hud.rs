@@ -0,0 +1,190 @@
+use anyhow::Result;
+
+use super::{
+    physics::{Car, IDLE_RPM},
+    road::Viewport,
+    rules::{format_time, RaceRules, LAPS},
+};
+use crate::{
+    data::Archive,
+    graphics::{
+        font::{Font, CHAR_SET_04, CHAR_SET_06},
+        Image, Point,
+    },
+    task::TICK_RATE,
+};
+
+/// The colours of the gauges, kept out of the palette so the sprites keep every entry of it.
+pub const GAUGE_BACK: u32 = 0xff20_2020;
+pub const GAUGE: u32 = 0xfffc_d000;
+pub const GAUGE_WARNING: u32 = 0xfffc_2828;
+
+/// The distance of the gauges from the edges of the viewport.
+const MARGIN: u32 = 4;
+
+/// The rows of the labels and of the values under them, from the top and from the bottom of the
+/// viewport.
+const TOP_LABELS: u32 = MARGIN;
+const TOP_VALUES: u32 = MARGIN + 10;
+const BOTTOM_LABELS: u32 = MARGIN + 24;
+const BOTTOM_VALUES: u32 = MARGIN + 14;
+
+const BAR_HEIGHT: u32 = 6;
+
+/// The share of the top revs and of the tank the gauges turn red at.
+const RED_LINE: f32 = 0.9;
+const LOW_FUEL: f32 = 0.1;
+
+/// What the HUD shows, read off the car and the rules every tick.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Gauges {
+    pub kmh: u32,
+    /// The revs as a share of the top ones, from the idle.
+    pub revs: f32,
+    /// The gear from 1.
+    pub gear: usize,
+    pub lap: u32,
+    pub lap_ticks: u32,
+    /// The whole seconds left of the time limit.
+    pub seconds_left: Option<u32>,
+    /// The position and the number of cars of a competition.
+    pub position: Option<(usize, usize)>,
+    pub fuel: f32,
+    /// The turbo pressure, `None` without one.
+    pub boost: Option<f32>,
+}
+
+impl Gauges {
+    pub fn of(car: &Car, rules: &RaceRules, position: Option<(usize, usize)>) -> Self {
+        let handling = car.handling();
+
+        Self {
+            kmh: car.kmh().round() as u32,
+            revs: ((car.rpm - IDLE_RPM) / (handling.max_rpm - IDLE_RPM)).clamp(0.0, 1.0),
+            gear: car.gear + 1,
+            lap: rules.lap(),
+            lap_ticks: rules.lap_ticks(),
+            seconds_left: rules.time_left().map(|x| x.div_ceil(TICK_RATE)),
+            position,
+            fuel: car.fuel,
+            boost: handling.turbo.then_some(car.boost),
+        }
+    }
+}
+
+/// Draws the gauges over the race view. Everything is placed from the edges of the viewport, so
+/// each half of the split screen gets its own.
+pub struct Hud {
+    digits: Font,
+    labels: Font,
+}
+
+impl Hud {
+    pub fn load(arc: &Archive) -> Result<Self> {
+        Ok(Self {
+            digits: Font::from(CHAR_SET_06, arc.get("C06")?),
+            labels: Font::from(CHAR_SET_04, arc.get("C04")?),
+        })
+    }
+
+    /// The characters of the fonts.
+    pub fn glyphs(&self) -> impl Iterator<Item = Image> + '_ {
+        self.digits.glyphs().chain(self.labels.glyphs())
+    }
+
+    pub fn draw(&self, buffer: &mut [u32], palette: &[u8], viewport: &Viewport, gauges: &Gauges) {
+        let width = viewport.width;
+        let bottom = viewport.height;
+
+        let mut label =
+            |x, y, text: &str| self.labels.print(at(buffer, viewport, x, y), text, palette);
+
+        label(MARGIN, TOP_LABELS, &format!("LAP {}-{LAPS}", gauges.lap));
+        label(width / 2 - 40, TOP_LABELS, "TIME");
+
+        if gauges.seconds_left.is_some() {
+            label(width / 2 + 30, TOP_LABELS, "LEFT");
+        }
+
+        if let Some((_, cars)) = gauges.position {
+            label(width - 56, TOP_LABELS, &format!("POS-{cars}"));
+        }
+
+        label(MARGIN, bottom - BOTTOM_LABELS, "KMH");
+        label(44, bottom - BOTTOM_LABELS, "GEAR");
+        label(84, bottom - BOTTOM_LABELS, "RPM");
+        label(164, bottom - BOTTOM_LABELS, "FUEL");
+
+        if gauges.boost.is_some() {
+            label(234, bottom - BOTTOM_LABELS, "TURBO");
+        }
+
+        let mut value =
+            |x, y, text: &str| self.digits.print(at(buffer, viewport, x, y), text, palette);
+
+        value(width / 2 - 40, TOP_VALUES, &lap_time(gauges.lap_ticks));
+
+        if let Some(seconds) = gauges.seconds_left {
+            value(width / 2 + 30, TOP_VALUES, &seconds.to_string());
+        }
+
+        if let Some((position, _)) = gauges.position {
+            value(width - 56, TOP_VALUES, &position.to_string());
+        }
+
+        value(MARGIN, bottom - BOTTOM_VALUES, &gauges.kmh.to_string());
+        value(44, bottom - BOTTOM_VALUES, &gauges.gear.to_string());
+
+        let y = bottom - BOTTOM_VALUES;
+        let warning = |x: bool| if x { GAUGE_WARNING } else { GAUGE };
+
+        bar(
+            buffer,
+            viewport,
+            (84, y),
+            64,
+            gauges.revs,
+            warning(gauges.revs > RED_LINE),
+        );
+        bar(
+            buffer,
+            viewport,
+            (164, y),
+            56,
+            gauges.fuel,
+            warning(gauges.fuel < LOW_FUEL),
+        );
+
+        if let Some(boost) = gauges.boost {
+            bar(buffer, viewport, (234, y), 56, boost, GAUGE);
+        }
+    }
+}
+
+/// Formats the ticks like [`format_time`] with spaces, the digits font has nothing else.
+pub fn lap_time(ticks: u32) -> String {
+    format_time(ticks).replace('-', " ")
+}
+
+/// Returns the buffer from the point of the viewport on.
+fn at<'a>(buffer: &'a mut [u32], viewport: &Viewport, x: u32, y: u32) -> &'a mut [u32] {
+    &mut buffer[Point::xy(viewport.x + x, viewport.y + y).index()..]
+}
+
+/// Draws a gauge filled to the share.
+fn bar(
+    buffer: &mut [u32],
+    viewport: &Viewport,
+    (x, y): (u32, u32),
+    width: u32,
+    share: f32,
+    color: u32,
+) {
+    let x = x as f32;
+    let filled = x + width as f32 * share.clamp(0.0, 1.0);
+
+    for y in y..y + BAR_HEIGHT {
+        viewport.fill(buffer, y, x, x + width as f32, GAUGE_BACK);
+        viewport.fill(buffer, y, x, filled, color);
+    }
+}
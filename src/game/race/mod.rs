@@ -0,0 +1,457 @@
+use anyhow::Result;
+use std::rc::Rc;
+
+use crate::{
+    engine::State,
+    game::{
+        options::{Acceleration, Course, Model, Race, Transmission},
+        recs::Track,
+        scores::{self, course_key, Kind},
+        screen::race_results,
+    },
+    graphics::Image,
+    input::{Action, DriverInput, InputDevice},
+    screen::{fade_in, fade_out, screen},
+    task::tick,
+};
+
+pub mod ai;
+pub mod environment;
+pub mod ghost;
+pub mod hud;
+pub mod physics;
+pub mod road;
+pub mod rules;
+pub mod scenery;
+
+use ai::Field;
+use environment::{draw_night, Effects, Fog, Particles};
+use ghost::{LapRecorder, GHOST_ALPHA};
+use hud::{Gauges, Hud};
+use physics::Car;
+use road::{
+    race_palette, Camera, Projection, Road, RoadColors, Viewport, DRAW_DISTANCE, ROAD_WIDTH,
+    SEGMENT_LENGTH,
+};
+use rules::{course_tracks, CourseResults, Event, RaceResult, RaceRules, Status};
+use scenery::{draw_billboards, load_images, Billboard, Roadside, CAR_KEY};
+
+/// The archive entry of the palette the sprites are drawn with.
+const SPRITE_PALETTE: &str = "I14";
+
+/// The number of opponents of a competition race.
+const FIELD_SIZE: usize = 9;
+
+/// Runs the races of the course of the options while a player qualifies, returns `false` if a
+/// player has backed out. Both players of a split-screen race drive the selected car.
+pub async fn race(state: &mut State, model: Model) -> Result<bool> {
+    let custom = match state.cfg.course {
+        Course::Unknown => Track::parse_or_default(&state.cfg.code),
+        _ => Track::default(),
+    };
+    let tracks = course_tracks(state.cfg.course, &custom);
+    let players = state.cfg.players_num.clamp(1, 2) as usize;
+    let mut results = vec![CourseResults::default(); players];
+
+    loop {
+        // a player who has failed to qualify is out of the rest of the course
+        let entrants = (0..players)
+            .filter(|&i| results[i].goes_on(tracks.len()))
+            .collect::<Vec<_>>();
+
+        let Some(&first) = entrants.first() else {
+            break;
+        };
+
+        let track = &tracks[results[first].races.len()];
+
+        let Some(race) = run(state, model, track, &entrants).await? else {
+            return Ok(false);
+        };
+
+        for (&i, result) in entrants.iter().zip(race) {
+            results[i].add(result);
+        }
+
+        let over = !results.iter().any(|x| x.goes_on(tracks.len()));
+        let records = enter_scores(state, &results, &entrants, over);
+
+        race_results(state, &results, &records, tracks.len()).await?;
+    }
+
+    Ok(true)
+}
+
+/// Enters the best laps of the players of `entrants` in the last race and, once the course is
+/// over, the scores of the players into the tables of the course. Returns whether each player has
+/// made a table.
+fn enter_scores(
+    state: &mut State,
+    results: &[CourseResults],
+    entrants: &[usize],
+    over: bool,
+) -> Vec<bool> {
+    // the demos race with the options of whoever recorded them
+    if state.session.borrow().is_playing() {
+        return vec![false; results.len()];
+    }
+
+    let course = course_key(state.cfg.course, state.cfg.race, &state.cfg.code);
+    let names = [state.cfg.p1_name.clone(), state.cfg.p2_name.clone()];
+
+    let records = results
+        .iter()
+        .zip(&names)
+        .enumerate()
+        .map(|(i, (results, name))| {
+            let lap = results
+                .races
+                .last()
+                .filter(|_| entrants.contains(&i))
+                .and_then(|x| x.best_lap)
+                .and_then(|x| state.scores.submit(&course, Kind::Lap, name, x));
+
+            let score = over
+                .then(|| {
+                    state
+                        .scores
+                        .submit(&course, Kind::Score, name, results.score())
+                })
+                .flatten();
+
+            lap.is_some() || score.is_some()
+        })
+        .collect::<Vec<_>>();
+
+    // a failed save costs the new entries only, not the game
+    if let Some(path) = state
+        .scores_path
+        .as_ref()
+        .filter(|_| records.contains(&true))
+    {
+        if let Err(e) = scores::save(&state.scores, path) {
+            eprintln!("{e:?}");
+        }
+    }
+
+    records
+}
+
+/// A player of the race, with the part of the screen the race is seen in.
+struct Player {
+    car: Car,
+    rules: RaceRules,
+    device: Rc<dyn InputDevice>,
+    accel: Acceleration,
+    viewport: Viewport,
+    recorder: LapRecorder,
+}
+
+impl Player {
+    /// The distance from the start line of the lap.
+    fn lap_z(&self, road: &Road) -> f32 {
+        self.car.z - (self.rules.lap() - 1) as f32 * road.length()
+    }
+}
+
+/// Runs a race on the track with the players of `entrants`, returns their results or `None` if
+/// one has backed out.
+async fn run(
+    state: &mut State,
+    model: Model,
+    track: &Track,
+    entrants: &[usize],
+) -> Result<Option<Vec<RaceResult>>> {
+    let effects = state.cfg.effects.unwrap_or(Effects::of(track.scenery));
+    // seeded by the track, so the weather doesn't change the field of a recorded race
+    let mut particles = match effects {
+        Effects { snow: true, .. } => Some(Particles::snow(track.seed())),
+        Effects { rain: true, .. } => Some(Particles::rain(track.seed())),
+        _ => None,
+    };
+
+    let mut road = Road::new(&track.segments());
+
+    let roadside = Roadside::load(&state.arc, track, road.segments().len())?;
+    let images = load_images(&state.arc, &[CAR_KEY])?;
+    let hud = Hud::load(&state.arc)?;
+
+    // the road takes the entries of the palette nothing else is drawn with
+    let glyphs = hud.glyphs().collect::<Vec<_>>();
+    let colors = RoadColors::unused_by(images.iter().chain(roadside.sprites()).chain(&glyphs));
+
+    let (_, base) = state.arc.get_with_palette(SPRITE_PALETTE)?;
+    let pal = race_palette(&base, &colors);
+
+    if effects.fog {
+        road = road.with_fog(Fog::new(&pal));
+    }
+
+    let mut field = match state.cfg.race {
+        Race::Competition => Field::new(track, FIELD_SIZE, state.rng.next_u64()),
+        Race::TimeLimit => Field::new(track, 0, 0),
+    };
+
+    for opponent in &mut field.opponents {
+        opponent.car.traction = effects.traction();
+    }
+
+    let settings = [
+        (state.cfg.p1_trans, state.cfg.p1_accel),
+        (state.cfg.p2_trans, state.cfg.p2_accel),
+    ];
+
+    let mut players = Viewport::split(entrants.len())
+        .into_iter()
+        .zip(entrants)
+        .enumerate()
+        .map(|(n, (viewport, &i))| {
+            let (transmission, accel) = settings[i];
+            let mut car = Car::new(model, transmission);
+            car.traction = effects.traction();
+
+            // side by side at the back of the grid
+            if entrants.len() > 1 {
+                car.x = if n == 0 { -0.4 } else { 0.4 };
+            }
+
+            let mut recorder = LapRecorder::default();
+            recorder.record(0, car.z, car.x);
+
+            Player {
+                car,
+                rules: RaceRules::new(state.cfg.race, track, road.length()),
+                device: state.device(i),
+                accel,
+                viewport,
+                recorder,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // the best lap of the track races along, the demos keep to themselves
+    let ghosts = state
+        .ghosts
+        .clone()
+        .filter(|_| !state.session.borrow().is_playing());
+    let mut best = ghosts.as_deref().and_then(|dir| ghost::load(dir, track));
+    let mut improved = false;
+
+    let mut first_time = true;
+
+    while players.iter().any(|x| x.rules.status() == Status::Racing) {
+        if players.iter().any(|x| x.device.just_pressed(Action::Back)) {
+            fade_out().await;
+
+            return Ok(None);
+        }
+
+        for player in &mut players {
+            // the cars of the players who are through roll on
+            let input = match player.rules.status() {
+                Status::Racing => DriverInput::read(player.device.as_ref(), player.accel),
+                _ => DriverInput::default(),
+            };
+
+            player.car.update(&input, &road);
+            player
+                .car
+                .collide_objects(roadside.placements(road.segment_at(player.car.z)));
+        }
+
+        let mut cars = players.iter().map(|x| x.car.clone()).collect::<Vec<_>>();
+
+        field.update(&road, &mut cars);
+
+        if let [a, b] = cars.as_mut_slice() {
+            a.collide(b);
+        }
+
+        let mut finished = Vec::new();
+
+        for (i, (player, car)) in players.iter_mut().zip(cars).enumerate() {
+            player.car = car;
+
+            if player.rules.status() != Status::Racing {
+                continue;
+            }
+
+            let event = player.rules.update(player.car.z);
+
+            if event == Some(Event::Finished) {
+                finished.push(i);
+            }
+
+            if let Some(Event::Lap(_) | Event::Finished) = event {
+                let lap_ticks = player.rules.lap_times().last().copied().unwrap_or_default();
+                let trace = player.recorder.finish(lap_ticks);
+
+                if best
+                    .as_ref()
+                    .is_none_or(|x| trace.lap_ticks() < x.lap_ticks())
+                {
+                    best = Some(trace);
+                    improved = true;
+                }
+            }
+
+            let lap_z = player.lap_z(&road);
+            player
+                .recorder
+                .record(player.rules.lap_ticks(), lap_z, player.car.x);
+        }
+
+        // the place is taken at the line, not where the car rolls to afterwards
+        for i in finished {
+            let position = position(&field, &players, &players[i].car);
+            players[i].rules.set_position(position);
+        }
+
+        if let Some(particles) = &mut particles {
+            particles.update();
+        }
+
+        for (i, player) in players.iter().enumerate() {
+            let camera = Camera {
+                z: player.car.z,
+                x: player.car.x * ROAD_WIDTH,
+                ..Camera::default()
+            };
+
+            let viewport = &player.viewport;
+            let projections = road.draw(screen(), &pal, &camera, viewport, &colors);
+
+            // a car keeps its image in every view, the players' ones come after the opponents'
+            let own = field.opponents.len() + i;
+            let others = field
+                .opponents
+                .iter()
+                .map(|x| &x.car)
+                .chain(players.iter().map(|x| &x.car))
+                .enumerate()
+                .filter(|(j, _)| *j != own);
+
+            let mut billboards = roadside.billboards(&projections, viewport);
+            billboards.extend(car_billboards(
+                others,
+                &images,
+                &road,
+                &camera,
+                &projections,
+                viewport,
+            ));
+
+            // the camera follows the car, which stays at the bottom of the view
+            if !images.is_empty() {
+                billboards.push(Billboard::player(&images[own % images.len()], viewport));
+            }
+
+            let ghost = best
+                .as_ref()
+                .filter(|_| player.rules.status() == Status::Racing)
+                .and_then(|x| x.position(player.rules.lap_ticks()))
+                .map(|(z, x)| Car {
+                    z: player.car.z - player.lap_z(&road) + z,
+                    x,
+                    ..Car::new(model, Transmission::Automatic)
+                });
+
+            billboards.extend(
+                car_billboards(
+                    ghost.iter().map(|x| (own, x)),
+                    &images,
+                    &road,
+                    &camera,
+                    &projections,
+                    viewport,
+                )
+                .into_iter()
+                .map(|x| Billboard {
+                    alpha: GHOST_ALPHA,
+                    ..x
+                }),
+            );
+
+            if let Some(fog) = road.fog() {
+                for billboard in &mut billboards {
+                    billboard.palette = Some(fog.billboard_palette(billboard.depth));
+                }
+            }
+
+            draw_billboards(&mut billboards, viewport, screen(), &pal);
+
+            if let Some(particles) = &particles {
+                particles.draw(screen(), viewport);
+            }
+
+            if effects.night {
+                draw_night(screen(), viewport);
+            }
+
+            let position = (state.cfg.race == Race::Competition).then(|| {
+                (
+                    position(&field, &players, &player.car),
+                    cars_count(&field, &players),
+                )
+            });
+            hud.draw(
+                screen(),
+                &pal,
+                viewport,
+                &Gauges::of(&player.car, &player.rules, position),
+            );
+        }
+
+        if first_time {
+            first_time = false;
+
+            fade_in().await;
+        }
+
+        tick().await;
+    }
+
+    fade_out().await;
+
+    // a failed save costs the ghost only, not the game
+    if let (Some(dir), Some(trace)) = (ghosts.as_deref().filter(|_| improved), &best) {
+        if let Err(e) = ghost::save(dir, track, trace) {
+            eprintln!("{e:?}");
+        }
+    }
+
+    Ok(Some(players.iter().map(|x| x.rules.result()).collect()))
+}
+
+/// The position of the car among the opponents and the players, from 1.
+fn position(field: &Field, players: &[Player], car: &Car) -> usize {
+    field.position(car) + players.iter().filter(|x| x.car.z > car.z).count()
+}
+
+fn cars_count(field: &Field, players: &[Player]) -> usize {
+    field.opponents.len() + players.len()
+}
+
+/// Returns the billboards of the cars on the drawn part of the road, each with the image of its
+/// index.
+fn car_billboards<'a, 'b>(
+    cars: impl Iterator<Item = (usize, &'b Car)>,
+    images: &'a [Image],
+    road: &Road,
+    camera: &Camera,
+    projections: &[Projection],
+    viewport: &Viewport,
+) -> Vec<Billboard<'a>> {
+    let visible = 0.0..DRAW_DISTANCE as f32 * SEGMENT_LENGTH;
+
+    cars.filter(|(_, x)| visible.contains(&(x.z - camera.z)) && !images.is_empty())
+        .filter_map(|(i, x)| {
+            let segment = road.segment_at(x.z);
+
+            projections
+                .iter()
+                .find(|p| p.index == segment)
+                .map(|p| Billboard::on(&images[i % images.len()], p, x.x, viewport))
+        })
+        .collect()
+}
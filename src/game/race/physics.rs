@@ -0,0 +1,333 @@
+use super::{
+    road::{Road, SEGMENT_LENGTH},
+    scenery::Placement,
+};
+use crate::{
+    game::options::{Model, Transmission},
+    input::DriverInput,
+};
+
+/// km/h per world unit per tick.
+pub const KMH_PER_UNIT: f32 = 2.8;
+
+pub const IDLE_RPM: f32 = 900.0;
+
+/// The width and the length of a car, in half widths of the road and world units.
+pub const CAR_WIDTH: f32 = 0.3;
+pub const CAR_LENGTH: f32 = SEGMENT_LENGTH;
+
+/// How wide the objects by the road are, in half widths of it.
+const OBJECT_WIDTH: f32 = 0.3;
+
+/// How far off the road a car gets, in half widths of it.
+const MAX_OFFSET: f32 = 3.0;
+
+/// The share of the top speed a car keeps off the road.
+const OFF_ROAD_SPEED: f32 = 0.3;
+const OFF_ROAD_DRAG: f32 = 0.04;
+
+/// The share of the speed a car keeps when it hits something.
+const CRASH_SPEED: f32 = 0.2;
+
+/// The ticks a car can't accelerate after a crash.
+const CRASH_TICKS: u32 = 70;
+
+/// The automatic gearbox shifts at these shares of the top revs.
+const SHIFT_UP: f32 = 0.92;
+const SHIFT_DOWN: f32 = 0.45;
+
+/// The automatic gearbox costs a little of the pull.
+const AUTOMATIC_PULL: f32 = 0.9;
+
+/// How hard the curves push the cars out at the top speed, in half widths of the road per tick
+/// and unit of the curve.
+const CENTRIFUGAL: f32 = 0.004;
+
+/// The world units a full tank lasts at full throttle, a little more than the longest race.
+const FUEL_RANGE: f32 = 3_000_000.0;
+
+/// The extra pull at full boost.
+const TURBO_PULL: f32 = 0.25;
+
+/// The share of the missing boost the turbo spools up by each tick.
+const TURBO_SPOOL: f32 = 0.03;
+
+/// The handling of a car model.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Handling {
+    /// World units per tick.
+    pub top_speed: f32,
+    /// The gain of speed per tick at full throttle in the lowest gear.
+    pub acceleration: f32,
+    pub braking: f32,
+    /// The loss of speed per tick when coasting at the top speed, it falls with the square of
+    /// the speed.
+    pub drag: f32,
+    /// Half widths of the road per tick at the full lock and the top speed.
+    pub steering: f32,
+    /// How much of the centrifugal push the tyres hold, `0.0..1.0`.
+    pub grip: f32,
+    pub max_rpm: f32,
+    /// The top speeds of the gears as shares of the car's one.
+    pub gears: &'static [f32],
+    pub turbo: bool,
+}
+
+impl Handling {
+    pub fn of(model: Model) -> &'static Handling {
+        match model {
+            Model::Esprit => &ESPRIT,
+            Model::Elan => &ELAN,
+            Model::M200 => &M200,
+        }
+    }
+}
+
+const ESPRIT: Handling = Handling {
+    top_speed: 95.0,
+    acceleration: 0.35,
+    braking: 1.2,
+    drag: 0.05,
+    steering: 0.045,
+    grip: 0.8,
+    max_rpm: 6500.0,
+    gears: &[0.3, 0.48, 0.66, 0.84, 1.0],
+    turbo: true,
+};
+
+const ELAN: Handling = Handling {
+    top_speed: 82.0,
+    acceleration: 0.4,
+    braking: 1.3,
+    drag: 0.05,
+    steering: 0.05,
+    grip: 0.9,
+    max_rpm: 7200.0,
+    gears: &[0.3, 0.5, 0.68, 0.85, 1.0],
+    turbo: true,
+};
+
+const M200: Handling = Handling {
+    top_speed: 100.0,
+    acceleration: 0.3,
+    braking: 1.1,
+    drag: 0.045,
+    steering: 0.04,
+    grip: 0.75,
+    max_rpm: 7600.0,
+    gears: &[0.28, 0.46, 0.64, 0.82, 1.0],
+    turbo: false,
+};
+
+/// A car on the road, advanced by [`Car::update`] once a tick.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Car {
+    pub model: Model,
+    pub transmission: Transmission,
+    /// The distance driven in world units, it doesn't wrap around the lap.
+    pub z: f32,
+    /// The offset from the centre of the road in half widths of it, the road is `-1.0..=1.0`.
+    pub x: f32,
+    /// World units per tick.
+    pub speed: f32,
+    /// The gear from 0.
+    pub gear: usize,
+    pub rpm: f32,
+    /// The ticks left until the car recovers from a crash.
+    pub crashed: u32,
+    /// The share of the tank left, the car runs dry at `0.0`.
+    pub fuel: f32,
+    /// The turbo pressure, `0.0..=1.0`, it stays at `0.0` without one.
+    pub boost: f32,
+    /// The share of the grip and the brakes the road gives, less when it's wet or icy.
+    pub traction: f32,
+}
+
+impl Car {
+    pub fn new(model: Model, transmission: Transmission) -> Self {
+        Self {
+            model,
+            transmission,
+            z: 0.0,
+            x: 0.0,
+            speed: 0.0,
+            gear: 0,
+            rpm: IDLE_RPM,
+            crashed: 0,
+            fuel: 1.0,
+            boost: 0.0,
+            traction: 1.0,
+        }
+    }
+
+    pub fn handling(&self) -> &'static Handling {
+        Handling::of(self.model)
+    }
+
+    pub fn kmh(&self) -> f32 {
+        self.speed * KMH_PER_UNIT
+    }
+
+    pub fn is_off_road(&self) -> bool {
+        self.x.abs() > 1.0
+    }
+
+    /// The top speed of the current gear.
+    fn gear_top(&self) -> f32 {
+        let handling = self.handling();
+
+        handling.gears[self.gear] * handling.top_speed
+    }
+
+    /// Advances the car by a tick.
+    pub fn update(&mut self, input: &DriverInput, road: &Road) {
+        let handling = self.handling();
+
+        self.shift(input);
+
+        let gear_top = self.gear_top();
+
+        // the low gears pull harder, the engine gives out towards the top of the gear
+        let pull = handling.acceleration / (1.0 + self.gear as f32 / 2.0);
+        let pull = match self.transmission {
+            Transmission::Manual => pull,
+            Transmission::Automatic => pull * AUTOMATIC_PULL,
+        };
+
+        let throttle = if self.crashed > 0 || self.fuel <= 0.0 {
+            0.0
+        } else {
+            input.throttle
+        };
+
+        if handling.turbo {
+            let target = throttle * (self.rpm - IDLE_RPM) / (handling.max_rpm - IDLE_RPM);
+
+            self.boost += (target - self.boost) * TURBO_SPOOL;
+        }
+
+        let pull = pull * (1.0 + TURBO_PULL * self.boost);
+        let headroom = (1.0 - self.speed / gear_top).max(0.0);
+        let ratio = self.speed / handling.top_speed;
+
+        self.speed += throttle * pull * headroom.sqrt();
+        self.speed -=
+            input.brake * handling.braking * self.traction + handling.drag * ratio * ratio;
+
+        // over-revving after a shift down
+        if self.speed > gear_top {
+            self.speed -= (self.speed - gear_top) * 0.05;
+        }
+
+        if self.is_off_road() && self.speed > OFF_ROAD_SPEED * handling.top_speed {
+            self.speed -= self.speed * OFF_ROAD_DRAG;
+        }
+
+        self.speed = self.speed.max(0.0);
+        self.crashed = self.crashed.saturating_sub(1);
+
+        let ratio = self.speed / handling.top_speed;
+        let curve = road.segments()[road.segment_at(self.z)].curve;
+
+        self.x += input.steer * handling.steering * ratio.min(1.0).sqrt();
+        self.x -= curve * CENTRIFUGAL * ratio * ratio * (1.0 - handling.grip * self.traction);
+        self.x = self.x.clamp(-MAX_OFFSET, MAX_OFFSET);
+
+        self.z += self.speed;
+        self.fuel = (self.fuel - throttle * self.speed / FUEL_RANGE).max(0.0);
+
+        self.rpm = IDLE_RPM + (handling.max_rpm - IDLE_RPM) * (self.speed / gear_top).min(1.0);
+    }
+
+    fn shift(&mut self, input: &DriverInput) {
+        let gears = self.handling().gears.len();
+
+        match self.transmission {
+            Transmission::Manual => {
+                if input.gear_up && self.gear + 1 < gears {
+                    self.gear += 1;
+                }
+
+                if input.gear_down && self.gear > 0 {
+                    self.gear -= 1;
+                }
+            }
+            Transmission::Automatic => {
+                let revs = self.speed / self.gear_top();
+
+                if revs > SHIFT_UP && self.gear + 1 < gears {
+                    self.gear += 1;
+                } else if revs < SHIFT_DOWN && self.gear > 0 {
+                    self.gear -= 1;
+                }
+            }
+        }
+    }
+
+    /// Crashes into the first object of the segment the car is on which it touches, returns
+    /// whether it has. The car is put beside the object so it drives off once it has recovered,
+    /// and it goes through objects until then.
+    pub fn collide_objects(&mut self, placements: &[Placement]) -> bool {
+        if self.crashed > 0 {
+            return false;
+        }
+
+        let reach = (CAR_WIDTH + OBJECT_WIDTH) / 2.0;
+
+        let Some(object) = placements
+            .iter()
+            .find(|x| (x.offset - self.x).abs() < reach)
+        else {
+            return false;
+        };
+
+        self.crash();
+
+        // on the side the car hit it from, unless that is too far off the road
+        let side = if self.x < object.offset { -1.0 } else { 1.0 };
+        let x = object.offset + side * reach;
+
+        self.x = match x.abs() > MAX_OFFSET {
+            true => object.offset - side * reach,
+            false => x,
+        };
+
+        true
+    }
+
+    /// Bumps the cars if they touch, the one behind runs into the one ahead. Returns whether
+    /// they have.
+    pub fn collide(&mut self, other: &mut Car) -> bool {
+        if (self.z - other.z).abs() >= CAR_LENGTH || (self.x - other.x).abs() >= CAR_WIDTH {
+            return false;
+        }
+
+        let (behind, ahead) = if self.z < other.z {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        if behind.speed > ahead.speed {
+            // the one behind gives its extra speed to the one ahead
+            let extra = behind.speed - ahead.speed;
+
+            behind.speed = ahead.speed;
+            ahead.speed += extra / 2.0;
+        }
+
+        // and both are pushed apart sideways
+        let push = (CAR_WIDTH - (behind.x - ahead.x).abs()) / 2.0;
+        let side = if behind.x < ahead.x { -1.0 } else { 1.0 };
+
+        behind.x += side * push;
+        ahead.x -= side * push;
+
+        true
+    }
+
+    pub fn crash(&mut self) {
+        self.speed *= CRASH_SPEED;
+        self.crashed = CRASH_TICKS;
+    }
+}
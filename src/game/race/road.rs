@@ -0,0 +1,416 @@
+use super::environment::Fog;
+use crate::{
+    game::recs::Segment,
+    graphics::{palette_color, Image, Point, SCREEN_HEIGHT, SCREEN_WIDTH},
+};
+
+/// The length of a segment in world units.
+pub const SEGMENT_LENGTH: f32 = 200.0;
+
+/// Half of the width of the road in world units.
+pub const ROAD_WIDTH: f32 = 2000.0;
+
+pub const CAMERA_HEIGHT: f32 = 1000.0;
+
+/// The distance of the camera from the projection plane, `1 / tan(50°)` for a field of view of
+/// 100°.
+pub const CAMERA_DEPTH: f32 = 0.84;
+
+/// The number of segments drawn ahead of the camera.
+pub const DRAW_DISTANCE: usize = 150;
+
+/// The number of segments of a stripe of the road.
+const RUMBLE_LENGTH: usize = 3;
+
+const LANES: u32 = 3;
+
+/// The width of the rumble strips and the lane markings, relative to the road.
+const RUMBLE_WIDTH: f32 = 0.15;
+const LANE_WIDTH: f32 = 0.03;
+
+/// The rise of a segment at the steepest slope.
+const HILL_HEIGHT: f32 = 60.0;
+
+/// How far a segment of the sharpest curve shifts the ones behind it, in world units per segment.
+const CURVE_SHIFT: f32 = 4.0;
+
+/// A part of the screen the road is drawn into, the split-screen mode has one per player.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Viewport {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Viewport {
+    pub const FULL: Viewport = Viewport {
+        x: 0,
+        y: 0,
+        width: SCREEN_WIDTH,
+        height: SCREEN_HEIGHT,
+    };
+
+    /// Divides the screen into a viewport per player, one above the other.
+    pub fn split(players: usize) -> Vec<Viewport> {
+        let height = SCREEN_HEIGHT / players.max(1) as u32;
+
+        (0..players as u32)
+            .map(|i| Viewport {
+                x: 0,
+                y: i * height,
+                width: SCREEN_WIDTH,
+                height,
+            })
+            .collect()
+    }
+
+    /// Fills the pixels from `x1` up to `x2` of the row `y`, clipped to the viewport.
+    pub fn fill(&self, buffer: &mut [u32], y: u32, x1: f32, x2: f32, color: u32) {
+        let x1 = x1.max(0.0) as u32;
+        let x2 = (x2.max(0.0) as u32).min(self.width);
+
+        if y >= self.height || x1 >= x2 {
+            return;
+        }
+
+        let start = Point::xy(self.x + x1, self.y + y).index();
+        buffer[start..start + (x2 - x1) as usize].fill(color);
+    }
+}
+
+/// The palette entries of the road. The light and the dark stripes alternate every few segments,
+/// so the speed shows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RoadColors {
+    pub sky: u8,
+    pub grass: [u8; 2],
+    pub rumble: [u8; 2],
+    pub road: [u8; 2],
+    pub lane: u8,
+}
+
+impl Default for RoadColors {
+    fn default() -> Self {
+        Self {
+            sky: 1,
+            grass: [2, 3],
+            rumble: [4, 5],
+            road: [6, 7],
+            lane: 8,
+        }
+    }
+}
+
+impl RoadColors {
+    /// Takes the last entries of the palette none of the `sprites` uses, so setting the colours
+    /// of the road doesn't change them. Falls back to the default entries if too few are left.
+    pub fn unused_by<'a>(sprites: impl IntoIterator<Item = &'a Image>) -> Self {
+        let mut used = [false; 256];
+
+        for entry in sprites.into_iter().flat_map(Image::entries) {
+            used[entry as usize] = true;
+        }
+
+        let free = (0..=u8::MAX)
+            .rev()
+            .filter(|x| !used[*x as usize])
+            .collect::<Vec<_>>();
+
+        match free[..] {
+            [lane, road1, road0, rumble1, rumble0, grass1, grass0, sky, ..] => Self {
+                sky,
+                grass: [grass0, grass1],
+                rumble: [rumble0, rumble1],
+                road: [road0, road1],
+                lane,
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// The entries with their colours, in 6-bit VGA components.
+    fn entries(&self) -> [(u8, [u8; 3]); 8] {
+        [
+            (self.sky, [22, 38, 63]),
+            (self.grass[0], [6, 38, 6]),
+            (self.grass[1], [4, 32, 4]),
+            (self.rumble[0], [63, 63, 63]),
+            (self.rumble[1], [50, 8, 8]),
+            (self.road[0], [27, 27, 27]),
+            (self.road[1], [25, 25, 25]),
+            (self.lane, [58, 58, 58]),
+        ]
+    }
+}
+
+/// Returns the palette of the race view: the `base` one, which the sprites are drawn with, and
+/// the entries of the `colors` of the road.
+pub fn race_palette(base: &[u8], colors: &RoadColors) -> Vec<u8> {
+    let mut palette = base.to_vec();
+    palette.resize(256 * 3, 0);
+
+    for (index, rgb) in colors.entries() {
+        palette[index as usize * 3..][..3].copy_from_slice(&rgb);
+    }
+
+    palette
+}
+
+/// A segment of the road in the world: the curve and the heights of its ends.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RoadSegment {
+    pub curve: f32,
+    pub y1: f32,
+    pub y2: f32,
+}
+
+/// Where the camera is: the distance along the road, the offset from its centre and the height
+/// above it, all in world units.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Camera {
+    pub z: f32,
+    pub x: f32,
+    pub height: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            z: 0.0,
+            x: 0.0,
+            height: CAMERA_HEIGHT,
+        }
+    }
+}
+
+/// A drawn segment: the screen position of its near end, relative to the viewport. The
+/// roadside objects of the segment are scaled by `scale` and clipped below `clip`, the top of
+/// the road drawn in front of it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Projection {
+    pub index: usize,
+    pub x: f32,
+    pub y: f32,
+    /// Half of the width of the road.
+    pub w: f32,
+    pub scale: f32,
+    pub clip: f32,
+}
+
+/// A closed road, the last segment leads to the first one.
+pub struct Road {
+    segments: Vec<RoadSegment>,
+    fog: Option<Fog>,
+}
+
+impl Road {
+    /// Builds the road of the generated track, see [`Track::segments`](crate::game::recs::Track::segments).
+    pub fn new(track: &[Segment]) -> Self {
+        let mut y = 0.0;
+
+        let mut segments = track
+            .iter()
+            .map(|x| {
+                let y1 = y;
+                y += x.slope * HILL_HEIGHT;
+
+                RoadSegment {
+                    curve: x.curve * CURVE_SHIFT,
+                    y1,
+                    y2: y,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // the hills don't have to even out, spread what's left over the lap so the end meets
+        // the start
+        let drift = y / segments.len().max(1) as f32;
+
+        for (i, segment) in segments.iter_mut().enumerate() {
+            segment.y1 -= drift * i as f32;
+            segment.y2 -= drift * (i + 1) as f32;
+        }
+
+        Self {
+            segments,
+            fog: None,
+        }
+    }
+
+    /// Draws the road fading into the fog with the distance.
+    pub fn with_fog(mut self, fog: Fog) -> Self {
+        self.fog = Some(fog);
+        self
+    }
+
+    pub fn fog(&self) -> Option<&Fog> {
+        self.fog.as_ref()
+    }
+
+    pub fn segments(&self) -> &[RoadSegment] {
+        &self.segments
+    }
+
+    /// The length of a lap in world units.
+    pub fn length(&self) -> f32 {
+        self.segments.len() as f32 * SEGMENT_LENGTH
+    }
+
+    /// Returns the index of the segment at the distance, which wraps around the lap.
+    pub fn segment_at(&self, z: f32) -> usize {
+        (z.rem_euclid(self.length()) / SEGMENT_LENGTH) as usize % self.segments.len()
+    }
+
+    /// Returns the height of the road at the distance.
+    pub fn height_at(&self, z: f32) -> f32 {
+        let segment = &self.segments[self.segment_at(z)];
+        let t = z.rem_euclid(SEGMENT_LENGTH) / SEGMENT_LENGTH;
+
+        segment.y1 + (segment.y2 - segment.y1) * t
+    }
+
+    /// Draws the sky, the grass and the road as the camera sees them, scanline by scanline from
+    /// the nearest segment on. Returns the segments which are in front of the camera, nearest
+    /// first.
+    pub fn draw(
+        &self,
+        buffer: &mut [u32],
+        palette: &[u8],
+        camera: &Camera,
+        viewport: &Viewport,
+        colors: &RoadColors,
+    ) -> Vec<Projection> {
+        let sky = palette_color(self.fog.as_ref().map_or(palette, |x| x.sky()), colors.sky);
+
+        for y in 0..viewport.height {
+            viewport.fill(buffer, y, 0.0, viewport.width as f32, sky);
+        }
+
+        let base = self.segment_at(camera.z);
+        let t = camera.z.rem_euclid(SEGMENT_LENGTH) / SEGMENT_LENGTH;
+        let camera_y = camera.height + self.height_at(camera.z);
+
+        // the curve shifts every segment after it, the one under the camera partly
+        let mut x = 0.0;
+        let mut dx = -self.segments[base].curve * t;
+
+        let mut clip = viewport.height as f32;
+        let mut projections = Vec::new();
+
+        for n in 0..DRAW_DISTANCE.min(self.segments.len()) {
+            let index = (base + n) % self.segments.len();
+            let segment = &self.segments[index];
+
+            // past the end of the lap the segments are ahead by a lap
+            let z = (base + n) as f32 * SEGMENT_LENGTH - camera.z.rem_euclid(self.length());
+
+            let p1 = project(x - camera.x, segment.y1 - camera_y, z, viewport);
+            let p2 = project(
+                x + dx - camera.x,
+                segment.y2 - camera_y,
+                z + SEGMENT_LENGTH,
+                viewport,
+            );
+
+            x += dx;
+            dx += segment.curve;
+
+            let (Some(p1), Some(p2)) = (p1, p2) else {
+                continue;
+            };
+
+            projections.push(Projection {
+                index,
+                x: p1.x,
+                y: p1.y,
+                w: p1.w,
+                scale: p1.scale,
+                clip,
+            });
+
+            // behind a hill, or the back of one
+            if p2.y >= clip || p2.y >= p1.y {
+                continue;
+            }
+
+            let stripe = (index / RUMBLE_LENGTH) % 2;
+            let palette = self.fog.as_ref().map_or(palette, |x| x.palette(z));
+            self.draw_segment(buffer, palette, viewport, colors, stripe, &p1, &p2, clip);
+
+            clip = p2.y;
+        }
+
+        projections
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn draw_segment(
+        &self,
+        buffer: &mut [u32],
+        palette: &[u8],
+        viewport: &Viewport,
+        colors: &RoadColors,
+        stripe: usize,
+        p1: &Projected,
+        p2: &Projected,
+        clip: f32,
+    ) {
+        let grass = palette_color(palette, colors.grass[stripe]);
+        let rumble = palette_color(palette, colors.rumble[stripe]);
+        let road = palette_color(palette, colors.road[stripe]);
+        let lane = palette_color(palette, colors.lane);
+
+        let top = p2.y.max(0.0).ceil() as u32;
+        let bottom = p1.y.min(clip).max(0.0).ceil() as u32;
+
+        for y in top..bottom {
+            let t = (y as f32 - p2.y) / (p1.y - p2.y);
+            let x = p2.x + (p1.x - p2.x) * t;
+            let w = p2.w + (p1.w - p2.w) * t;
+
+            let r = w * (1.0 + RUMBLE_WIDTH);
+
+            viewport.fill(buffer, y, 0.0, viewport.width as f32, grass);
+            viewport.fill(buffer, y, x - r, x + r, rumble);
+            viewport.fill(buffer, y, x - w, x + w, road);
+
+            // the markings are on the light stripes only, so they are dashed
+            if stripe == 0 {
+                let l = (w * LANE_WIDTH).max(0.5);
+
+                for k in 1..LANES {
+                    let lx = x - w + 2.0 * w * k as f32 / LANES as f32;
+
+                    viewport.fill(buffer, y, lx - l, lx + l, lane);
+                }
+            }
+        }
+    }
+}
+
+/// A point projected to the viewport.
+struct Projected {
+    x: f32,
+    y: f32,
+    w: f32,
+    scale: f32,
+}
+
+/// Projects a point relative to the camera, `None` if it's behind the projection plane.
+fn project(x: f32, y: f32, z: f32, viewport: &Viewport) -> Option<Projected> {
+    if z <= CAMERA_DEPTH {
+        return None;
+    }
+
+    let scale = CAMERA_DEPTH / z;
+    let half_width = viewport.width as f32 / 2.0;
+    let half_height = viewport.height as f32 / 2.0;
+
+    Some(Projected {
+        x: half_width + scale * x * half_width,
+        y: half_height - scale * y * half_height,
+        w: scale * ROAD_WIDTH * half_width,
+        scale,
+    })
+}
@@ -0,0 +1,304 @@
+use crate::{
+    game::{
+        options::{Course, Race},
+        recs::{Scenery, Track, MAX_VALUE},
+    },
+    task::TICK_RATE,
+};
+
+pub const LAPS: u32 = 3;
+
+/// The checkpoints of a lap, the last one is the start line.
+pub const CHECKPOINTS: u32 = 4;
+
+/// The places which score, the rest get nothing.
+pub const POINTS: [u32; 6] = [10, 6, 4, 3, 2, 1];
+
+/// The position a competition race has to be finished in to go on to the next one.
+pub const QUALIFYING_POSITION: usize = 5;
+
+/// The score of a race qualified in on top of its points.
+pub const QUALIFYING_BONUS: u32 = 5;
+
+/// The speed, in world units per tick, the time limit is set for at the lowest and the highest
+/// difficulty.
+const EASY_PACE: f32 = 55.0;
+const HARD_PACE: f32 = 70.0;
+
+/// The time given at the start on top of the first section.
+const START_GRACE: u32 = 5 * TICK_RATE;
+
+/// Returns the races of the course. The unknown course is the one of the RECS code given.
+pub fn course_tracks(course: Course, custom: &Track) -> Vec<Track> {
+    let stage = |scenery, curves, hills, difficulty| Track {
+        curves,
+        hills,
+        steepness: hills,
+        scatter: 13,
+        obstacles: difficulty / 4,
+        difficulty,
+        length: 6,
+        variation: 0,
+        scenery,
+    };
+
+    use Scenery::*;
+
+    match course {
+        Course::T1 => vec![
+            stage(Forest, 6, 4, 2),
+            stage(Desert, 8, 2, 4),
+            stage(Night, 10, 6, 6),
+        ],
+        Course::T2 => vec![
+            stage(Snow, 10, 8, 8),
+            stage(Fog, 12, 10, 10),
+            stage(Marsh, 12, 6, 12),
+            stage(Motorway, 6, 2, 14),
+        ],
+        Course::T3 => vec![
+            stage(Storm, 14, 12, 16),
+            stage(Mountains, 16, 20, 18),
+            stage(Roadworks, 14, 8, 20),
+            stage(Windy, 18, 14, 22),
+            stage(Future, 20, 16, 24),
+        ],
+        Course::Circular => Scenery::ALL
+            .into_iter()
+            .zip(0..)
+            .map(|(scenery, n)| Track {
+                variation: n,
+                ..stage(scenery, 8 + n, 4 + n, 2 * n)
+            })
+            .collect(),
+        Course::Unknown => vec![*custom],
+    }
+}
+
+/// What happened during a tick of the race.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    /// A checkpoint has been passed, the time limit has been extended by the ticks.
+    Checkpoint(u32),
+    /// A lap has been completed, the number of the next one.
+    Lap(u32),
+    Finished,
+    OutOfTime,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Status {
+    Racing,
+    Finished,
+    OutOfTime,
+}
+
+/// Keeps the laps, the checkpoints and the clock of the player's race. Times are in ticks.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RaceRules {
+    race: Race,
+    lap_length: f32,
+    /// The ticks the time limit gives for a section between two checkpoints.
+    section_time: u32,
+    time_left: u32,
+    passed: u32,
+    ticks: u32,
+    lap_start: u32,
+    lap_times: Vec<u32>,
+    status: Status,
+    /// The position the race has been finished in.
+    position: Option<usize>,
+}
+
+impl RaceRules {
+    pub fn new(race: Race, track: &Track, lap_length: f32) -> Self {
+        let level = track.difficulty.min(MAX_VALUE) as f32 / MAX_VALUE as f32;
+        let pace = EASY_PACE + (HARD_PACE - EASY_PACE) * level;
+        let section_time = (lap_length / CHECKPOINTS as f32 / pace).ceil() as u32;
+
+        Self {
+            race,
+            lap_length,
+            section_time,
+            time_left: section_time + START_GRACE,
+            passed: 0,
+            ticks: 0,
+            lap_start: 0,
+            lap_times: Vec::new(),
+            status: Status::Racing,
+            position: None,
+        }
+    }
+
+    /// Advances the clock by a tick with the player at the distance `z` from the start.
+    pub fn update(&mut self, z: f32) -> Option<Event> {
+        if self.status != Status::Racing {
+            return None;
+        }
+
+        self.ticks += 1;
+
+        let checkpoint = (z / (self.lap_length / CHECKPOINTS as f32)).max(0.0) as u32;
+
+        // one at a time, a checkpoint jumped over in a tick is passed in the next one
+        if checkpoint > self.passed {
+            self.passed += 1;
+
+            if self.passed.is_multiple_of(CHECKPOINTS) {
+                self.lap_times.push(self.ticks - self.lap_start);
+                self.lap_start = self.ticks;
+
+                if self.passed / CHECKPOINTS == LAPS {
+                    self.status = Status::Finished;
+                    return Some(Event::Finished);
+                }
+
+                return Some(Event::Lap(self.lap()));
+            }
+
+            if self.race == Race::TimeLimit {
+                self.time_left += self.section_time;
+                return Some(Event::Checkpoint(self.section_time));
+            }
+
+            return None;
+        }
+
+        if self.race == Race::TimeLimit {
+            self.time_left -= 1;
+
+            if self.time_left == 0 {
+                self.status = Status::OutOfTime;
+                return Some(Event::OutOfTime);
+            }
+        }
+
+        None
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// The lap the player is on, from 1.
+    pub fn lap(&self) -> u32 {
+        (self.passed / CHECKPOINTS + 1).min(LAPS)
+    }
+
+    /// The ticks left of the time limit, `None` in a competition.
+    pub fn time_left(&self) -> Option<u32> {
+        (self.race == Race::TimeLimit).then_some(self.time_left)
+    }
+
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// The ticks since the start of the lap.
+    pub fn lap_ticks(&self) -> u32 {
+        self.ticks - self.lap_start
+    }
+
+    pub fn lap_times(&self) -> &[u32] {
+        &self.lap_times
+    }
+
+    /// Keeps the position the player is at on [`Event::Finished`], the car rolls on and may lose
+    /// places afterwards.
+    pub fn set_position(&mut self, position: usize) {
+        self.position = Some(position);
+    }
+
+    /// Returns the result of the race, which has to be over.
+    pub fn result(&self) -> RaceResult {
+        let finished = self.status == Status::Finished;
+        let position = self
+            .position
+            .filter(|_| self.race == Race::Competition && finished);
+
+        let qualified = finished
+            && match self.race {
+                Race::TimeLimit => true,
+                Race::Competition => position.is_some_and(|x| x <= QUALIFYING_POSITION),
+            };
+
+        RaceResult {
+            status: self.status,
+            position,
+            time: self.ticks,
+            best_lap: self.lap_times.iter().copied().min(),
+            points: position.map_or(0, points),
+            qualified,
+        }
+    }
+}
+
+/// Formats the ticks as minutes, seconds and hundredths, the font has no other separator than
+/// the dash.
+pub fn format_time(ticks: u32) -> String {
+    let hundredths = ticks as u64 * 100 / TICK_RATE as u64;
+
+    format!(
+        "{}-{:02}-{:02}",
+        hundredths / 6000,
+        hundredths / 100 % 60,
+        hundredths % 100
+    )
+}
+
+/// The points of a position from 1.
+pub fn points(position: usize) -> u32 {
+    position
+        .checked_sub(1)
+        .and_then(|x| POINTS.get(x))
+        .copied()
+        .unwrap_or(0)
+}
+
+/// How a race of the course has gone for the player.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RaceResult {
+    pub status: Status,
+    /// The finishing position of a competition.
+    pub position: Option<usize>,
+    /// Ticks from the start to the finish, or to the end of the time.
+    pub time: u32,
+    pub best_lap: Option<u32>,
+    pub points: u32,
+    /// Whether the player goes on to the next race.
+    pub qualified: bool,
+}
+
+/// The results of the races of a course so far.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct CourseResults {
+    pub races: Vec<RaceResult>,
+}
+
+impl CourseResults {
+    pub fn add(&mut self, result: RaceResult) {
+        self.races.push(result);
+    }
+
+    pub fn points(&self) -> u32 {
+        self.races.iter().map(|x| x.points).sum()
+    }
+
+    /// The score of the course for the tables, the time limit races score by qualifying only.
+    pub fn score(&self) -> u32 {
+        self.races
+            .iter()
+            .map(|x| x.points + x.qualified as u32 * QUALIFYING_BONUS)
+            .sum()
+    }
+
+    /// Whether the player has qualified in every race of a course of `races`.
+    pub fn is_complete(&self, races: usize) -> bool {
+        self.races.len() == races && self.races.iter().all(|x| x.qualified)
+    }
+
+    /// Whether the next race is to be run.
+    pub fn goes_on(&self, races: usize) -> bool {
+        self.races.len() < races && self.races.iter().all(|x| x.qualified)
+    }
+}
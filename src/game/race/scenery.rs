@@ -0,0 +1,270 @@
+use anyhow::Result;
+
+use super::road::{Projection, Viewport, ROAD_WIDTH};
+use crate::{
+    data::Archive,
+    game::recs::{Scenery, Track, MAX_VALUE},
+    graphics::{Bitmap, Clip, Image},
+    rng::Rng,
+};
+
+/// The world units per pixel of a sprite, the road is 128 pixels wide.
+pub const SPRITE_UNIT: f32 = ROAD_WIDTH / 64.0;
+
+/// The segments after the start line which are kept clear.
+const CLEAR_START: usize = 10;
+
+/// The chance of an object beside a segment at the densest scatter.
+const MAX_SCATTER: f32 = 0.5;
+
+/// The chance of an obstacle on a segment at the most obstacles.
+const MAX_OBSTACLES: f32 = 0.05;
+
+/// Returns the bitmaps of the roadside objects of the scenery, every frame of them is an object.
+///
+/// Which bitmaps the original draws with each scenery hasn't been traced yet. Until it is, the
+/// sprite bitmaps of the archive from S80 on, the ones `tests/bitmap.rs` decodes, are handed out
+/// in their order: three to the first scenery and two to each of the others, up to SBD. SBE is
+/// packed like the cars, SC0 to SC2 are the [`OBSTACLE_KEYS`] and the ones after them are left
+/// out.
+pub fn scenery_keys(scenery: Scenery) -> &'static [&'static str] {
+    match scenery {
+        Scenery::Forest => &["S80", "S81", "S82"],
+        Scenery::Night => &["S83", "S84"],
+        Scenery::Fog => &["S85", "S86"],
+        Scenery::Snow => &["S88", "S89"],
+        Scenery::Desert => &["S8A", "S90"],
+        Scenery::Storm => &["S92", "S96"],
+        Scenery::Marsh => &["S97", "SA6"],
+        Scenery::Motorway => &["SB2", "SB5"],
+        Scenery::Roadworks => &["SB6", "SB7"],
+        Scenery::Windy => &["SB8", "SB9"],
+        Scenery::Future => &["SBA", "SBB"],
+        Scenery::Mountains => &["SBC", "SBD"],
+    }
+}
+
+/// The bitmaps of the obstacles on the road, the same for every scenery.
+pub const OBSTACLE_KEYS: [&str; 3] = ["SC0", "SC1", "SC2"];
+
+/// The bitmap of the cars.
+pub const CAR_KEY: &str = "S70";
+
+/// The share of the width or the height of the viewport the player's car takes, whichever is
+/// less, and its distance from the bottom.
+const PLAYER_CAR_SHARE: f32 = 0.3;
+const PLAYER_CAR_MARGIN: u32 = 4;
+
+/// Returns the decoding parameters of the sprite bitmap, see [`Bitmap::from`].
+fn bitmap_params(key: &str) -> (u8, u8) {
+    match key {
+        "S70" => (255, 25),
+        "SBE" => (255, 27),
+        _ => (0, 240),
+    }
+}
+
+/// Loads every frame of the bitmaps.
+pub fn load_images(arc: &Archive, keys: &[&str]) -> Result<Vec<Image>> {
+    let mut images = Vec::new();
+
+    for key in keys {
+        let (par1, par2) = bitmap_params(key);
+        let bitmap = Bitmap::from(arc.get(key)?, par1, par2);
+
+        images.extend((0..bitmap.len()).map(|x| bitmap.frame(x)));
+    }
+
+    Ok(images)
+}
+
+/// An object by the road: the segment, the offset from the centre of the road in half widths of
+/// it and the image among the roadside objects or the obstacles.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Placement {
+    pub segment: usize,
+    pub offset: f32,
+    pub image: usize,
+    pub obstacle: bool,
+}
+
+/// Places the objects of the track, the same ones every time: the scatter sets how many line
+/// the road, the obstacles how many are on it.
+pub fn place(track: &Track, segments: usize, images: usize, obstacles: usize) -> Vec<Placement> {
+    let mut rng = Rng::new(track.seed());
+    let mut placements = Vec::new();
+
+    let scatter = MAX_SCATTER * track.scatter.min(MAX_VALUE) as f32 / MAX_VALUE as f32;
+    let obstacle = MAX_OBSTACLES * track.obstacles.min(MAX_VALUE) as f32 / MAX_VALUE as f32;
+
+    for segment in CLEAR_START..segments {
+        for side in [-1.0, 1.0] {
+            if images > 0 && rng.next_f32() < scatter {
+                placements.push(Placement {
+                    segment,
+                    offset: side * (1.4 + 1.6 * rng.next_f32()),
+                    image: rng.below(images as u32) as usize,
+                    obstacle: false,
+                });
+            }
+        }
+
+        if obstacles > 0 && rng.next_f32() < obstacle {
+            placements.push(Placement {
+                segment,
+                offset: 1.6 * rng.next_f32() - 0.8,
+                image: rng.below(obstacles as u32) as usize,
+                obstacle: true,
+            });
+        }
+    }
+
+    placements
+}
+
+/// A sprite standing on the road, the bottom centre at `(x, y)` of the viewport.
+pub struct Billboard<'a> {
+    pub image: &'a Image,
+    pub x: f32,
+    pub y: f32,
+    /// Screen pixels per pixel of the image.
+    pub scale: f32,
+    /// The distance from the camera, the farthest are drawn first.
+    pub depth: f32,
+    /// The top of the road in front of the sprite, which hides it below.
+    pub clip: f32,
+    /// The share of the sprite mixed over what is behind it, `1.0` is solid.
+    pub alpha: f32,
+    /// The palette of the sprite instead of the one of the view, the fogged ones far away.
+    pub palette: Option<&'a [u8]>,
+}
+
+impl<'a> Billboard<'a> {
+    /// Stands the image on the projected segment, `offset` half widths of the road from its
+    /// centre.
+    pub fn on(image: &'a Image, projection: &Projection, offset: f32, viewport: &Viewport) -> Self {
+        let half_width = viewport.width as f32 / 2.0;
+
+        Self {
+            image,
+            x: projection.x + projection.w * offset,
+            y: projection.y,
+            scale: projection.scale * SPRITE_UNIT * half_width,
+            depth: 1.0 / projection.scale,
+            clip: projection.clip,
+            alpha: 1.0,
+            palette: None,
+        }
+    }
+
+    /// Stands the image of the player's car at the bottom of the viewport, in front of the rest.
+    pub fn player(image: &'a Image, viewport: &Viewport) -> Self {
+        let width = viewport.width as f32 * PLAYER_CAR_SHARE / image.size.width as f32;
+        let height = viewport.height as f32 * PLAYER_CAR_SHARE / image.size.height as f32;
+
+        Self {
+            image,
+            x: viewport.width as f32 / 2.0,
+            y: (viewport.height - PLAYER_CAR_MARGIN) as f32,
+            scale: width.min(height),
+            depth: 0.0,
+            clip: viewport.height as f32,
+            alpha: 1.0,
+            palette: None,
+        }
+    }
+}
+
+/// Draws the billboards in the painter's order, the nearest ones over the rest.
+pub fn draw_billboards(
+    billboards: &mut [Billboard],
+    viewport: &Viewport,
+    buffer: &mut [u32],
+    palette: &[u8],
+) {
+    billboards.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+
+    for billboard in billboards.iter() {
+        let width = billboard.image.size.width as f32 * billboard.scale;
+        let height = billboard.image.size.height as f32 * billboard.scale;
+
+        let clip = Clip {
+            left: viewport.x as i32,
+            top: viewport.y as i32,
+            right: (viewport.x + viewport.width) as i32,
+            bottom: viewport.y as i32 + billboard.clip.min(viewport.height as f32) as i32,
+        };
+
+        billboard.image.draw_blended(
+            (
+                viewport.x as i32 + (billboard.x - width / 2.0).round() as i32,
+                viewport.y as i32 + (billboard.y - height).round() as i32,
+            ),
+            billboard.scale,
+            clip,
+            buffer,
+            billboard.palette.unwrap_or(palette),
+            billboard.alpha,
+        );
+    }
+}
+
+/// The objects along the road of a track.
+pub struct Roadside {
+    images: Vec<Image>,
+    obstacles: Vec<Image>,
+    by_segment: Vec<Vec<Placement>>,
+}
+
+impl Roadside {
+    pub fn load(arc: &Archive, track: &Track, segments: usize) -> Result<Self> {
+        let images = load_images(arc, scenery_keys(track.scenery))?;
+        let obstacles = load_images(arc, &OBSTACLE_KEYS)?;
+
+        Ok(Self::new(images, obstacles, track, segments))
+    }
+
+    pub fn new(images: Vec<Image>, obstacles: Vec<Image>, track: &Track, segments: usize) -> Self {
+        let mut by_segment = vec![Vec::new(); segments];
+
+        for placement in place(track, segments, images.len(), obstacles.len()) {
+            by_segment[placement.segment].push(placement);
+        }
+
+        Self {
+            images,
+            obstacles,
+            by_segment,
+        }
+    }
+
+    /// The images of the objects and of the obstacles.
+    pub fn sprites(&self) -> impl Iterator<Item = &Image> {
+        self.images.iter().chain(&self.obstacles)
+    }
+
+    pub fn placements(&self, segment: usize) -> &[Placement] {
+        &self.by_segment[segment]
+    }
+
+    /// Returns the billboards of the objects on the projected segments.
+    pub fn billboards(
+        &self,
+        projections: &[Projection],
+        viewport: &Viewport,
+    ) -> Vec<Billboard<'_>> {
+        projections
+            .iter()
+            .flat_map(|projection| {
+                self.by_segment[projection.index].iter().map(|x| {
+                    let image = match x.obstacle {
+                        true => &self.obstacles[x.image],
+                        false => &self.images[x.image],
+                    };
+
+                    Billboard::on(image, projection, x.offset, viewport)
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,264 @@
+use anyhow::{anyhow, ensure, Result};
+use std::fmt;
+
+use crate::rng::Rng;
+
+/// The length of a code: 9 letters, a dash and the two digit checksum, e.g. `XKXCJGFJH-33`.
+pub const CODE_LEN: usize = 12;
+
+const LETTERS: usize = 9;
+
+/// The highest value of a letter, `Z`. A space is 0, `A` is 1.
+pub const MAX_VALUE: u8 = 26;
+
+/// The number of segments of the shortest track, every step of the length adds
+/// [`SEGMENTS_PER_LENGTH`].
+pub const MIN_SEGMENTS: usize = 1000;
+pub const SEGMENTS_PER_LENGTH: usize = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Scenery {
+    #[default]
+    Forest = 0,
+    Night = 1,
+    Fog = 2,
+    Snow = 3,
+    Desert = 4,
+    Storm = 5,
+    Marsh = 6,
+    Motorway = 7,
+    Roadworks = 8,
+    Windy = 9,
+    Future = 10,
+    Mountains = 11,
+}
+
+impl Scenery {
+    pub const ALL: [Scenery; 12] = [
+        Scenery::Forest,
+        Scenery::Night,
+        Scenery::Fog,
+        Scenery::Snow,
+        Scenery::Desert,
+        Scenery::Storm,
+        Scenery::Marsh,
+        Scenery::Motorway,
+        Scenery::Roadworks,
+        Scenery::Windy,
+        Scenery::Future,
+        Scenery::Mountains,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Forest => "FOREST",
+            Self::Night => "NIGHT",
+            Self::Fog => "FOG",
+            Self::Snow => "SNOW",
+            Self::Desert => "DESERT",
+            Self::Storm => "STORM",
+            Self::Marsh => "MARSH",
+            Self::Motorway => "MOTORWAY",
+            Self::Roadworks => "ROADWORKS",
+            Self::Windy => "WINDY",
+            Self::Future => "FUTURE",
+            Self::Mountains => "MOUNTAINS",
+        }
+    }
+}
+
+/// A track of the Racing Course Editing System. Every value but the scenery goes from 0 to
+/// [`MAX_VALUE`] and takes one letter of the code, in the order of the fields, so the code and
+/// the track are two forms of the same thing. The order is this port's own, like the checksum.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Track {
+    /// How many of the sections are curves.
+    pub curves: u8,
+    /// How many of the sections go up or down.
+    pub hills: u8,
+    /// How steep the hills are.
+    pub steepness: u8,
+    /// How densely the scenery lines the road.
+    pub scatter: u8,
+    /// How many obstacles are on the road.
+    pub obstacles: u8,
+    /// How well the opponents drive.
+    pub difficulty: u8,
+    pub length: u8,
+    /// Picks one of the tracks of the same values.
+    pub variation: u8,
+    pub scenery: Scenery,
+}
+
+/// A piece of the road. The curve bends it to the right for positive values, the slope lifts it
+/// for positive ones, both go from -1.0 to 1.0.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Segment {
+    pub curve: f32,
+    pub slope: f32,
+}
+
+impl Track {
+    /// Parses and checks a code: the letters, the dash, the checksum and the scenery. Lower case
+    /// letters are taken as upper case ones.
+    pub fn parse(code: &str) -> Result<Self> {
+        let invalid = || anyhow!("Invalid RECS code '{code}'!");
+
+        let code = code.to_ascii_uppercase();
+        let (letters, checksum) = code.split_once('-').ok_or_else(invalid)?;
+
+        ensure!(
+            letters.len() == LETTERS && checksum.len() == 2,
+            "RECS code '{code}' isn't {CODE_LEN} characters long!"
+        );
+
+        let values = letters
+            .chars()
+            .map(|c| match c {
+                ' ' => Some(0),
+                'A'..='Z' => Some(c as u8 - b'A' + 1),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+
+        let checksum = checksum
+            .parse::<u8>()
+            .ok()
+            .filter(|_| checksum.bytes().all(|x| x.is_ascii_digit()))
+            .ok_or_else(invalid)?;
+
+        ensure!(
+            checksum == self::checksum(&values),
+            "Wrong checksum of RECS code '{code}'!"
+        );
+
+        let scenery = *Scenery::ALL
+            .get(values[8] as usize)
+            .ok_or_else(|| anyhow!("Unknown scenery in RECS code '{code}'!"))?;
+
+        Ok(Self {
+            curves: values[0],
+            hills: values[1],
+            steepness: values[2],
+            scatter: values[3],
+            obstacles: values[4],
+            difficulty: values[5],
+            length: values[6],
+            variation: values[7],
+            scenery,
+        })
+    }
+
+    /// Parses the code of the options, which may have been typed in or edited by hand. An invalid
+    /// code is reported and gives the default track, a straight road.
+    pub fn parse_or_default(code: &str) -> Self {
+        Self::parse(code).unwrap_or_else(|e| {
+            let track = Self::default();
+
+            eprintln!("{e} Using '{track}' instead.");
+            track
+        })
+    }
+
+    /// Returns the code of the track. The values are clamped to [`MAX_VALUE`].
+    pub fn code(&self) -> String {
+        self.to_string()
+    }
+
+    fn values(&self) -> [u8; LETTERS] {
+        [
+            self.curves,
+            self.hills,
+            self.steepness,
+            self.scatter,
+            self.obstacles,
+            self.difficulty,
+            self.length,
+            self.variation,
+            self.scenery as u8,
+        ]
+        .map(|x| x.min(MAX_VALUE))
+    }
+
+    /// The seed of everything generated for the track, the same for every track of the same
+    /// code.
+    pub fn seed(&self) -> u64 {
+        self.values()
+            .iter()
+            .fold(0, |seed, x| seed * (MAX_VALUE as u64 + 1) + *x as u64)
+    }
+
+    /// Generates the road. The same track always gives the same road.
+    pub fn segments(&self) -> Vec<Segment> {
+        let mut rng = Rng::new(self.seed());
+
+        let count = MIN_SEGMENTS + self.length.min(MAX_VALUE) as usize * SEGMENTS_PER_LENGTH;
+        let mut segments = Vec::with_capacity(count);
+
+        let share = |value: u8| value.min(MAX_VALUE) as u32;
+
+        while segments.len() < count {
+            let len = rng.range(20..80) as usize;
+
+            let curve = match rng.below(MAX_VALUE as u32) < share(self.curves) {
+                true => (0.3 + 0.7 * rng.next_f32()) * if rng.below(2) == 0 { -1.0 } else { 1.0 },
+                false => 0.0,
+            };
+
+            let slope = match rng.below(MAX_VALUE as u32) < share(self.hills) {
+                true => {
+                    let steepness = (1 + share(self.steepness)) as f32 / (1 + MAX_VALUE) as f32;
+
+                    steepness * (rng.next_f32() * 2.0 - 1.0)
+                }
+                false => 0.0,
+            };
+
+            // ease in and out of the section, so the road doesn't kink
+            for i in 0..len {
+                let ease = (std::f32::consts::PI * i as f32 / len as f32).sin();
+
+                segments.push(Segment {
+                    curve: curve * ease,
+                    slope: slope * ease,
+                });
+            }
+        }
+
+        segments.truncate(count);
+        segments
+    }
+}
+
+impl fmt::Display for Track {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = self.values();
+
+        for value in values {
+            let c = match value {
+                0 => ' ',
+                _ => (b'A' + value - 1) as char,
+            };
+
+            write!(f, "{c}")?;
+        }
+
+        write!(f, "-{:02}", checksum(&values))
+    }
+}
+
+/// The checksum of the letter values, each weighted by its position: eight times the weighted
+/// sum plus 38, modulo 101, with 100 written as `00`. The game's own routine and the meaning of
+/// its letters aren't known, so this layout and rule are this port's: codes of the original may
+/// be rejected and codes made here may not be understood by it. The weights make a changed or
+/// swapped letter change the checksum.
+fn checksum(values: &[u8]) -> u8 {
+    let sum = values
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (i as u32 + 1) * *x as u32)
+        .sum::<u32>();
+
+    ((8 * sum + 38) % 101 % 100) as u8
+}
@@ -0,0 +1,206 @@
+use anyhow::{Context, Result};
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    options::{Course, Race},
+    settings::settings_path,
+};
+
+/// The version of the scores file which this build writes.
+pub const SCORES_VERSION: u32 = 1;
+
+/// The entries kept of each table.
+pub const TABLE_SIZE: usize = 5;
+
+const SCORES_FILE: &str = "scores.txt";
+
+/// Returns the path of the scores file, which is kept next to the settings one.
+pub fn scores_path() -> Option<PathBuf> {
+    settings_path().map(|x| x.with_file_name(SCORES_FILE))
+}
+
+/// Returns the key of the tables of the course in the race mode, the RECS code stands for the
+/// course of the options. The modes score differently, so each has tables of its own.
+pub fn course_key(course: Course, race: Race, code: &str) -> String {
+    let course = match course {
+        Course::Unknown => code.to_string(),
+        _ => course.name().to_uppercase(),
+    };
+
+    format!("{}/{course}", race.name())
+}
+
+/// The kinds of table, which sort their entries differently.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Kind {
+    /// The best laps in ticks, the shortest first.
+    Lap,
+    /// The course scores, the highest first.
+    Score,
+}
+
+impl Kind {
+    fn name(&self) -> &'static str {
+        match self {
+            Kind::Lap => "lap",
+            Kind::Score => "score",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "lap" => Some(Kind::Lap),
+            "score" => Some(Kind::Score),
+            _ => None,
+        }
+    }
+
+    fn is_better(&self, a: u32, b: u32) -> bool {
+        match self {
+            Kind::Lap => a < b,
+            Kind::Score => a > b,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Entry {
+    pub name: String,
+    pub value: u32,
+}
+
+/// The best laps and scores of every course played.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Scores {
+    tables: BTreeMap<(String, Kind), Vec<Entry>>,
+}
+
+impl Scores {
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// Returns the entries of the table, the best first.
+    pub fn table(&self, course: &str, kind: Kind) -> &[Entry] {
+        self.tables
+            .get(&(course.to_string(), kind))
+            .map_or(&[], |x| x.as_slice())
+    }
+
+    /// Enters the value if it makes the table, returns its place from 0 then. Equal values keep
+    /// the older entry first.
+    pub fn submit(&mut self, course: &str, kind: Kind, name: &str, value: u32) -> Option<usize> {
+        let table = self.tables.entry((course.to_string(), kind)).or_default();
+
+        let place = table
+            .iter()
+            .position(|x| kind.is_better(value, x.value))
+            .unwrap_or(table.len());
+
+        if place >= TABLE_SIZE {
+            return None;
+        }
+
+        table.insert(
+            place,
+            Entry {
+                name: name.to_string(),
+                value,
+            },
+        );
+        table.truncate(TABLE_SIZE);
+
+        Some(place)
+    }
+
+    /// Parses the lines of `course|kind|value|name`. A bad line is skipped and reported among the
+    /// returned warnings, as is a version newer than [`SCORES_VERSION`].
+    pub fn parse(text: &str) -> (Self, Vec<String>) {
+        let mut scores = Self::default();
+        let mut warnings = Vec::new();
+
+        for (n, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(value) = line.strip_prefix("version=") {
+                match value.trim().parse::<u32>() {
+                    Ok(version) if version > SCORES_VERSION => warnings.push(format!(
+                        "version {version} is newer than {SCORES_VERSION}, unknown lines are skipped"
+                    )),
+                    Ok(_) => {}
+                    Err(_) => warnings.push(format!("line {}: invalid version '{value}'", n + 1)),
+                }
+
+                continue;
+            }
+
+            let mut fields = line.splitn(4, '|');
+
+            let entry = (|| {
+                let course = fields.next()?;
+                let kind = Kind::from_name(fields.next()?)?;
+                let value = fields.next()?.parse().ok()?;
+                let name = fields.next()?;
+
+                Some((course, kind, value, name))
+            })();
+
+            match entry {
+                Some((course, kind, value, name)) => {
+                    scores.submit(course, kind, name, value);
+                }
+                None => warnings.push(format!("line {}: expected 'course|kind|value|name'", n + 1)),
+            }
+        }
+
+        (scores, warnings)
+    }
+}
+
+impl fmt::Display for Scores {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((course, kind), table) in &self.tables {
+            for entry in table {
+                writeln!(f, "{course}|{}|{}|{}", kind.name(), entry.value, entry.name)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Loads the scores, none if the file doesn't exist yet.
+pub fn load(path: &Path) -> Result<Scores> {
+    if !path.exists() {
+        return Ok(Scores::default());
+    }
+
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read '{}'!", path.display()))?;
+
+    let (scores, warnings) = Scores::parse(&text);
+
+    for warning in warnings {
+        eprintln!("{}: {warning}", path.display());
+    }
+
+    Ok(scores)
+}
+
+pub fn save(scores: &Scores, path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(
+        path,
+        format!("# Lotus III scores\nversion={SCORES_VERSION}\n{scores}"),
+    )
+    .with_context(|| format!("Failed to write '{}'!", path.display()))
+}
@@ -1,11 +1,11 @@
 use anyhow::Result;
-use winit::keyboard::{Key, NamedKey};
 
 use crate::{
     engine::State,
     graphics::Sprite,
+    input::Action,
     screen::{fade_in, fade_out, screen},
-    task::yield_now,
+    task::tick,
 };
 
 pub async fn audio_tuner(state: &mut State) -> Result<Option<u8>> {
@@ -17,15 +17,26 @@ pub async fn audio_tuner(state: &mut State) -> Result<Option<u8>> {
     let mut track_num = 1;
 
     let selection = 'main: loop {
-        yield_now().await;
-
-        for k in state.input.borrow().keys() {
-            match k {
-                Key::Named(NamedKey::ArrowLeft) => track_num -= 1,
-                Key::Named(NamedKey::ArrowRight) => track_num += 1,
-                Key::Named(NamedKey::Enter) => break 'main Some(track_num),
-                Key::Named(NamedKey::Escape) => break 'main None,
-                _ => {}
+        tick().await;
+
+        {
+            let input = state.input.borrow();
+            let menu = state.menu_input();
+
+            if menu.pressed(&input, Action::SteerLeft) {
+                track_num -= 1;
+            }
+
+            if menu.pressed(&input, Action::SteerRight) {
+                track_num += 1;
+            }
+
+            if menu.pressed(&input, Action::Confirm) {
+                break 'main Some(track_num);
+            }
+
+            if menu.pressed(&input, Action::Back) {
+                break 'main None;
             }
         }
 
@@ -36,11 +47,11 @@ pub async fn audio_tuner(state: &mut State) -> Result<Option<u8>> {
             // let font_c06 = Font::from(CHAR_SET_06, state.arc.get("C06")?);
             // state.screen.print(&font_c06, "01:02:03").show((60, 140).into());
 
-            fade_in(None).await;
+            fade_in().await;
         }
     };
 
-    fade_out(None).await;
+    fade_out().await;
 
     Ok(selection)
 }
@@ -1,7 +1,11 @@
 mod audio_tuner;
 mod protection;
+mod results;
+mod score_table;
 mod select_model;
 
 pub use audio_tuner::*;
 pub use protection::*;
+pub use results::*;
+pub use score_table::*;
 pub use select_model::*;
@@ -1,22 +1,24 @@
 use anyhow::Result;
-use std::time::Instant;
 
 use crate::{
     engine::State,
+    game::code_wheel::{CODE_LEN, CODE_WHEEL_FILE, HELMET_SIZE, TRIES},
     graphics::{
         font::{Font, CHAR_SET_03},
-        Size, Sprite,
+        Sprite,
     },
-    input::{BACKSPACE_CHAR, ENTER_CHAR, ESCAPE_CHAR},
+    input::{Action, BACKSPACE_CHAR, ENTER_CHAR, ESCAPE_CHAR},
     screen::{fade_out, screen, screen_at},
-    task::yield_now,
+    task::{tick, TICK_RATE},
 };
 
-pub async fn protection(state: &mut State) -> Result<()> {
-    const HELMET_SIZE: Size = Size::wh(48, 40); // 24 helmets of 48x40 bytes each
-
-    let instant = Instant::now();
+/// How long the notice of a skipped protection stays up unless a key is pressed.
+const NOTICE_TICKS: u32 = 4 * TICK_RATE;
 
+/// Asks for the code of the code wheel, returns `false` if the player has given up or entered a
+/// wrong code [`TRIES`] times, the game quits then. Without the codes of a wheel with the
+/// helmets of `I22` there is nothing to ask, the player is told the protection is skipped.
+pub async fn protection(state: &mut State) -> Result<bool> {
     let (i21, ref pal) = state.arc.get_with_palette("I21")?;
     let i22 = state
         .arc
@@ -24,27 +26,78 @@ pub async fn protection(state: &mut State) -> Result<()> {
 
     let bgr = Sprite::from(i21);
     let font = Font::from(CHAR_SET_03, state.arc.get("C03")?);
-    let helmet1 = Sprite::from(i22[0].clone()).with_size(HELMET_SIZE);
-    let helmet2 = Sprite::from(i22[1].clone()).with_size(HELMET_SIZE);
+
+    let wheel = match &state.code_wheel {
+        Some(wheel) if wheel.helmets() == i22.len() => wheel,
+        wheel => {
+            let problem = match wheel {
+                Some(_) => "DOES NOT MATCH THE HELMETS",
+                None => "IS MISSING",
+            };
+
+            eprintln!(
+                "The code wheel of '{CODE_WHEEL_FILE}' {}, skipping the protection.",
+                problem.to_lowercase()
+            );
+
+            bgr.draw(screen(), pal);
+            font.print(screen_at((60, 140)), &format!("CODE WHEEL {problem}"), pal);
+            font.print(screen_at((60, 155)), "PROTECTION SKIPPED", pal);
+
+            let menu = state.menu_input();
+
+            for _ in 0..NOTICE_TICKS {
+                tick().await;
+
+                let input = state.input.borrow();
+
+                if menu.pressed(&input, Action::Confirm) || menu.pressed(&input, Action::Back) {
+                    break;
+                }
+            }
+
+            fade_out().await;
+
+            return Ok(true);
+        }
+    };
+
+    let challenge = wheel.challenge(&mut state.rng);
+    let expected = wheel.code(&challenge).to_string();
+
+    let top = Sprite::from(i22[challenge.top].clone()).with_size(HELMET_SIZE);
+    let bottom = Sprite::from(i22[challenge.bottom].clone()).with_size(HELMET_SIZE);
 
     let mut first_time = true;
-    let mut enter_code: Option<String> = None;
+    let mut tries = 0;
     let mut code = String::new();
 
-    'main: loop {
-        yield_now().await;
+    let passed = 'main: loop {
+        tick().await;
 
         let mut key_pressed = false;
 
         for c in state.input.borrow().chars() {
             match c {
-                ENTER_CHAR | ESCAPE_CHAR => {
-                    break 'main;
+                ESCAPE_CHAR => break 'main false,
+                ENTER_CHAR if code.len() == CODE_LEN => {
+                    if code == expected {
+                        break 'main true;
+                    }
+
+                    tries += 1;
+
+                    if tries == TRIES {
+                        break 'main false;
+                    }
+
+                    code.clear();
+                    key_pressed = true;
                 }
                 BACKSPACE_CHAR if code.pop().is_some() => {
                     key_pressed = true;
                 }
-                _ if code.len() < 3 && (c.is_ascii_alphabetic() || c.is_ascii_digit()) => {
+                _ if code.len() < CODE_LEN && (c.is_ascii_alphabetic() || c.is_ascii_digit()) => {
                     code.push(c.to_ascii_uppercase());
                     key_pressed = true;
                 }
@@ -56,21 +109,24 @@ pub async fn protection(state: &mut State) -> Result<()> {
             first_time = false;
 
             bgr.draw(screen(), pal);
-            helmet1.draw(screen_at((141, 13)), pal);
-            helmet2.draw(screen_at((141, 73)), pal);
+            top.draw(screen_at((141, 13)), pal);
+            bottom.draw(screen_at((141, 73)), pal);
+
+            let prompt = match tries {
+                0 => "ENTER CODE FOR WINDOW",
+                _ => "WRONG CODE FOR WINDOW",
+            };
 
             font.print(
                 screen_at((60, 140)),
-                enter_code.get_or_insert_with(|| {
-                    format!("ENTER CODE FOR WINDOW {}", instant.elapsed().as_millis())
-                }),
+                &format!("{prompt} {}", challenge.window),
                 pal,
             );
             font.print(screen_at((150, 165)), &code, pal);
         }
-    }
+    };
 
-    fade_out(None).await;
+    fade_out().await;
 
-    Ok(())
+    Ok(passed)
 }
@@ -0,0 +1,98 @@
+use anyhow::Result;
+
+use crate::{
+    engine::State,
+    game::race::rules::{format_time, CourseResults, Status},
+    graphics::font::{Font, CHAR_SET_04},
+    input::Action,
+    screen::{fade_in, fade_out, screen, screen_at},
+    task::{tick, TICK_RATE},
+};
+
+const LEFT: u32 = 20;
+const COLUMN_WIDTH: u32 = 160;
+const TOP: u32 = 40;
+const ROW_HEIGHT: u32 = 20;
+
+/// How long the results stay up unless a key is pressed.
+const SHOW_TICKS: u32 = 8 * TICK_RATE;
+
+/// Shows how the last race of the course has gone, a column per player, and who has made the
+/// tables of the course.
+pub async fn race_results(
+    state: &mut State,
+    results: &[CourseResults],
+    records: &[bool],
+    races: usize,
+) -> Result<()> {
+    let (_, ref pal) = state.arc.get_with_palette("I14")?;
+    let font = Font::from(CHAR_SET_04, state.arc.get("C04")?);
+
+    let names = [&state.cfg.p1_name, &state.cfg.p2_name];
+
+    screen().fill(0xff00_0000);
+
+    for (column, (player, name)) in (0..).zip(results.iter().zip(names)) {
+        let x = LEFT + column * COLUMN_WIDTH;
+        let mut lines = result_lines(player, races);
+
+        if results.len() > 1 {
+            lines.insert(0, name.clone());
+        }
+
+        if records.get(column as usize) == Some(&true) {
+            lines.push("NEW RECORD".to_string());
+        }
+
+        for (row, line) in (0..).zip(&lines) {
+            font.print(screen_at((x, TOP + row * ROW_HEIGHT)), line, pal);
+        }
+    }
+
+    fade_in().await;
+
+    let device = state.device(0);
+
+    for _ in 0..SHOW_TICKS {
+        tick().await;
+
+        if device.just_pressed(Action::Confirm) || device.just_pressed(Action::Back) {
+            break;
+        }
+    }
+
+    fade_out().await;
+
+    Ok(())
+}
+
+fn result_lines(results: &CourseResults, races: usize) -> Vec<String> {
+    let Some(last) = results.races.last() else {
+        return Vec::new();
+    };
+
+    let mut lines = vec![format!("RACE {} OF {races}", results.races.len())];
+
+    if let Some(position) = last.position {
+        lines.push(format!("POSITION {position}"));
+        lines.push(format!("POINTS {} TOTAL {}", last.points, results.points()));
+    }
+
+    lines.push(format!("TIME {}", format_time(last.time)));
+
+    if let Some(best_lap) = last.best_lap {
+        lines.push(format!("BEST LAP {}", format_time(best_lap)));
+    }
+
+    lines.push(
+        match (last.status, last.qualified) {
+            (Status::OutOfTime, _) => "OUT OF TIME",
+            (_, false) => "NOT QUALIFIED",
+            _ if results.is_complete(races) => "COURSE COMPLETE",
+            _ => "QUALIFIED",
+        }
+        .to_string(),
+    );
+
+    lines
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+
+use crate::{
+    engine::State,
+    game::{
+        options::Course,
+        race::rules::format_time,
+        scores::{course_key, Kind},
+    },
+    graphics::font::{Font, CHAR_SET_04},
+    screen::{fade_in, fade_out, screen, screen_at},
+    task::sleep,
+};
+
+const TITLE_POS: (u32, u32) = (116, 8);
+const BLOCK_LEFT: u32 = 16;
+const BLOCK_TOP: u32 = 30;
+const BLOCK_WIDTH: u32 = 160;
+const BLOCK_HEIGHT: u32 = 80;
+const ROW_HEIGHT: u32 = 11;
+const VALUE_OFFSET: u32 = 90;
+
+/// The entries of the score table shown of each course.
+const SHOWN: usize = 3;
+
+/// The characters of a name which fit before the value.
+const NAME_LEN: usize = 12;
+
+const COURSES: [Course; 4] = [Course::T1, Course::T2, Course::T3, Course::Circular];
+
+/// Shows the best scores and the lap record of each course in the race mode of the options for a
+/// while.
+pub async fn show_scores(state: &mut State) -> Result<()> {
+    let (_, ref pal) = state.arc.get_with_palette("I14")?;
+    let font = Font::from(CHAR_SET_04, state.arc.get("C04")?);
+
+    screen().fill(0xff00_0000);
+
+    font.print(screen_at(TITLE_POS), "BEST SCORES", pal);
+
+    for (i, course) in (0..).zip(COURSES) {
+        let x = BLOCK_LEFT + i % 2 * BLOCK_WIDTH;
+        let y = BLOCK_TOP + i / 2 * BLOCK_HEIGHT;
+        let key = course_key(course, state.cfg.race, "");
+
+        font.print(screen_at((x, y)), &course.name().to_uppercase(), pal);
+
+        let scores = state.scores.table(&key, Kind::Score);
+
+        for row in 0..SHOWN {
+            let y = y + (row as u32 + 1) * ROW_HEIGHT;
+
+            let (name, value) = match scores.get(row) {
+                Some(entry) => (
+                    entry.name.chars().take(NAME_LEN).collect(),
+                    entry.value.to_string(),
+                ),
+                None => ("-".to_string(), "-".to_string()),
+            };
+
+            font.print(screen_at((x, y)), &name, pal);
+            font.print(screen_at((x + VALUE_OFFSET, y)), &value, pal);
+        }
+
+        let lap = match state.scores.table(&key, Kind::Lap).first() {
+            Some(entry) => format_time(entry.value),
+            None => "-".to_string(),
+        };
+
+        let y = y + (SHOWN as u32 + 1) * ROW_HEIGHT;
+
+        font.print(screen_at((x, y)), "LAP", pal);
+        font.print(screen_at((x + VALUE_OFFSET, y)), &lap, pal);
+    }
+
+    fade_in().await;
+    sleep(5000).await;
+    fade_out().await;
+
+    Ok(())
+}
@@ -1,12 +1,13 @@
 use anyhow::Result;
-use winit::keyboard::{Key, NamedKey};
+use std::cell::RefCell;
 
 use crate::{
     engine::State,
     game::options::Model,
     graphics::{Point, Size, Sprite},
+    input::{Action, InputHelper, MenuInput},
     screen::{fade_in, fade_out, screen, screen_at},
-    task::yield_now,
+    task::{select, sleep, tick, Either},
 };
 
 const ANIM_DELAY: u64 = 100;
@@ -19,81 +20,70 @@ const KEYS: &[(&str, &str)] = &[
     ("I13", "I12"), // M200
 ];
 
+const CONTROLS: [Action; 4] = [
+    Action::SteerLeft,
+    Action::SteerRight,
+    Action::Confirm,
+    Action::Back,
+];
+
 pub async fn select_model(state: &mut State) -> Result<Option<Model>> {
     let mut model = Model::default();
 
-    let mut sprites: Option<(Sprite, Vec<Sprite>, Vec<u8>)> = None;
-    let mut frame: Option<usize> = None;
-    let mut fade = false;
-
-    let selection = 'main: loop {
-        yield_now().await;
-
-        for k in state.input.borrow().keys() {
-            match k {
-                Key::Named(NamedKey::ArrowLeft) => {
-                    model = model.prev();
-                    sprites = None;
-                    fade = true;
-                }
-                Key::Named(NamedKey::ArrowRight) => {
-                    model = model.next();
-                    sprites = None;
-                    fade = true;
-                }
-                Key::Named(NamedKey::Enter) => break 'main Some(model),
-                Key::Named(NamedKey::Escape) => break 'main None,
-                _ => {}
-            }
-        }
+    let selection = loop {
+        let (bgr_key, ani_key) = KEYS[model as usize];
+        let (bgr, ref pal) = state.arc.get_with_palette(bgr_key)?;
+        let anim = state
+            .arc
+            .get_series(ani_key, ANIM_SIZE.width * ANIM_SIZE.height)?
+            .into_iter()
+            .map(|x| Sprite::from(x).with_size(ANIM_SIZE))
+            .collect::<Vec<_>>();
 
-        if sprites.is_none() {
-            let (bgr_key, ani_key) = KEYS[model as usize];
-            let (bgr, pal) = state.arc.get_with_palette(bgr_key)?;
-            let anim = state
-                .arc
-                .get_series(ani_key, ANIM_SIZE.width * ANIM_SIZE.height)?
-                .into_iter()
-                .map(|x| Sprite::from(x).with_size(ANIM_SIZE))
-                .collect();
-
-            frame = None;
-            sprites = Some((Sprite::from(bgr), anim, pal));
-        }
+        Sprite::from(bgr).draw(screen(), pal);
+        anim[0].draw(screen_at(ANIM_POS), pal);
 
-        if fade {
-            fade = false;
+        fade_in().await;
 
-            fade_out(None).await;
-        }
+        let menu = state.menu_input();
 
-        let Some((bgr, anim, pal)) = &sprites else {
-            break None;
+        let action = match select(animate(&anim, pal), read_action(&state.input, &menu)).await {
+            Either::Left(()) => read_action(&state.input, &menu).await,
+            Either::Right(action) => action,
         };
 
-        bgr.draw(screen(), pal);
+        match action {
+            Action::SteerLeft => model = model.prev(),
+            Action::SteerRight => model = model.next(),
+            Action::Confirm => break Some(model),
+            _ => break None,
+        }
 
-        match frame {
-            Some(ref mut i) => {
-                std::thread::sleep(std::time::Duration::from_millis(ANIM_DELAY));
+        fade_out().await;
+    };
 
-                anim[*i].draw(screen_at(ANIM_POS), pal);
-                *i += 1;
+    fade_out().await;
 
-                if *i == anim.len() {
-                    *i = 0;
-                }
-            }
-            None => {
-                anim[0].draw(screen_at(ANIM_POS), pal);
-                frame = Some(1);
+    Ok(selection)
+}
 
-                fade_in(None).await;
-            }
-        }
-    };
+async fn animate(anim: &[Sprite], pal: &[u8]) {
+    for sprite in anim.iter().cycle().skip(1) {
+        sleep(ANIM_DELAY).await;
 
-    fade_out(None).await;
+        sprite.draw(screen_at(ANIM_POS), pal);
+    }
+}
 
-    Ok(selection)
+async fn read_action(input: &RefCell<InputHelper>, menu: &MenuInput) -> Action {
+    loop {
+        tick().await;
+
+        if let Some(action) = CONTROLS
+            .into_iter()
+            .find(|a| menu.pressed(&input.borrow(), *a))
+        {
+            return action;
+        }
+    }
 }
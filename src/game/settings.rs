@@ -0,0 +1,108 @@
+use anyhow::{Context, Result};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use super::options::{self, Config};
+
+/// The version of the settings file which this build writes.
+pub const SETTINGS_VERSION: u32 = 1;
+
+const SETTINGS_DIR: &str = "lotus3";
+const SETTINGS_FILE: &str = "settings.cfg";
+
+/// Returns the path of the settings file in the user's config directory, `None` if there is no
+/// such directory.
+pub fn settings_path() -> Option<PathBuf> {
+    config_dir().map(|x| x.join(SETTINGS_DIR).join(SETTINGS_FILE))
+}
+
+#[cfg(windows)]
+fn config_dir() -> Option<PathBuf> {
+    env::var_os("APPDATA").map(PathBuf::from)
+}
+
+#[cfg(target_os = "macos")]
+fn config_dir() -> Option<PathBuf> {
+    env::var_os("HOME").map(|x| PathBuf::from(x).join("Library/Application Support"))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|x| !x.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|x| PathBuf::from(x).join(".config")))
+}
+
+/// Loads the settings, the defaults if the file doesn't exist yet. See [`parse`] for how the
+/// content is checked.
+pub fn load(path: &Path) -> Result<Config> {
+    if !path.exists() {
+        return Ok(Config::new());
+    }
+
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read '{}'!", path.display()))?;
+
+    let (cfg, warnings) = parse(&text);
+
+    for warning in warnings {
+        eprintln!("{}: {warning}", path.display());
+    }
+
+    Ok(cfg)
+}
+
+pub fn save(cfg: &Config, path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(path, to_string(cfg))
+        .with_context(|| format!("Failed to write '{}'!", path.display()))
+}
+
+pub fn to_string(cfg: &Config) -> String {
+    format!(
+        "# Lotus III settings, edit while the game isn't running\nversion={SETTINGS_VERSION}\n{cfg}"
+    )
+}
+
+/// Parses the settings over the defaults. The file is edited by hand, so a bad line doesn't spoil
+/// the others: it's skipped and reported among the returned warnings, and its option keeps the
+/// default value.
+/// A file without a version is taken as one of [`SETTINGS_VERSION`].
+pub fn parse(text: &str) -> (Config, Vec<String>) {
+    let mut warnings = Vec::new();
+    let mut version = SETTINGS_VERSION;
+    let mut entries = Vec::new();
+
+    for (n, entry) in options::key_values(text) {
+        match entry {
+            Ok(("version", value)) => match value.trim().parse() {
+                Ok(x) => version = x,
+                Err(_) => warnings.push(format!("line {n}: invalid version '{value}'")),
+            },
+            Ok((key, value)) => entries.push((n, key.to_string(), value.to_string())),
+            Err(e) => warnings.push(format!("line {n}: {e}")),
+        }
+    }
+
+    if version > SETTINGS_VERSION {
+        warnings.push(format!(
+            "version {version} is newer than {SETTINGS_VERSION}, unknown options are skipped"
+        ));
+    }
+
+    let mut cfg = Config::new();
+
+    for (n, key, value) in entries {
+        if let Err(e) = cfg.set(&key, &value) {
+            warnings.push(format!("line {n}: {e}"));
+        }
+    }
+
+    (cfg, warnings)
+}
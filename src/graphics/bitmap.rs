@@ -1,4 +1,4 @@
-use crate::graphics::{Point, SCREEN_WIDTH};
+use crate::graphics::{Clip, Image, Point, Size};
 
 pub struct Bitmap {
     data: Vec<u8>,
@@ -11,20 +11,40 @@ impl Bitmap {
         }
     }
 
-    pub fn draw(&self, index: usize, point: Point, buffer: &mut [u32], palette: &[u8]) {
-        let pos = index << 3; // 8-byte table
+    /// The number of frames, the last entry of the table is marked by `0xFF`.
+    pub fn len(&self) -> usize {
+        self.data
+            .chunks(8)
+            .position(|x| x.len() < 8 || x[7] == 0xFF)
+            .map_or(0, |x| x + 1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn size(&self, index: usize) -> Size {
+        let pos = index << 3;
+
+        Size::wh(
+            u16::from_le_bytes([self.data[pos + 2], self.data[pos + 3]]) as u32,
+            u16::from_le_bytes([self.data[pos + 4], self.data[pos + 5]]) as u32,
+        )
+    }
+
+    /// Unpacks the frame, so it can be drawn at any size.
+    pub fn frame(&self, index: usize) -> Image {
+        let pos = index << 3;
 
         let op_pos = u16::from_le_bytes([self.data[pos], self.data[pos + 1]]) << 4;
-        let repeat = (u16::from_le_bytes([self.data[pos + 2], self.data[pos + 3]]) + 7) >> 3;
-        let height = u16::from_le_bytes([self.data[pos + 4], self.data[pos + 5]]) as usize;
+        let size = self.size(index);
+        let repeat = (size.width + 7) >> 3;
 
         let mut data = self.data.iter().skip(op_pos as usize);
+        let mut pixels = vec![None; (size.width * size.height) as usize];
 
-        let xx = point.x as usize;
-        let yy = point.y as usize;
-
-        for y in yy..yy + height {
-            let mut offset = y * SCREEN_WIDTH as usize + xx;
+        for row in pixels.chunks_mut(size.width as usize) {
+            let mut x = 0;
 
             for _ in 0..repeat {
                 for o in OP_CODES[*data.next().unwrap() as usize]
@@ -32,25 +52,31 @@ impl Bitmap {
                     .flatten()
                 {
                     match o {
-                        Code::Skip(num) => offset += num,
+                        Code::Skip(num) => x += num,
                         Code::Draw(num) => {
                             for _ in 0..num {
-                                let val = *data.next().unwrap() as usize * 3;
-
-                                buffer[offset] = u32::from_be_bytes([
-                                    255,
-                                    palette[val] << 2,
-                                    palette[val + 1] << 2,
-                                    palette[val + 2] << 2,
-                                ]);
-
-                                offset += 1;
+                                row[x] = Some(*data.next().unwrap());
+                                x += 1;
                             }
                         }
                     }
                 }
             }
         }
+
+        Image::new(size, pixels)
+    }
+
+    /// Draws the frame with the top left corner at the point, the transparent pixels are left
+    /// alone.
+    pub fn draw(&self, index: usize, point: Point, buffer: &mut [u32], palette: &[u8]) {
+        self.frame(index).draw_scaled(
+            (point.x as i32, point.y as i32),
+            1.0,
+            Clip::SCREEN,
+            buffer,
+            palette,
+        );
     }
 }
 
@@ -174,7 +200,7 @@ enum Code {
     Draw(u8),
 }
 
-const OP_CODES: [[Option<Code>; 8]; 256] = create_table::<8, 256>();
+static OP_CODES: [[Option<Code>; 8]; 256] = create_table::<8, 256>();
 
 const fn create_table<const X: usize, const Y: usize>() -> [[Option<Code>; X]; Y] {
     let mut codes = [[None; X]; Y];
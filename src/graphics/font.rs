@@ -1,4 +1,4 @@
-use super::Bitmap;
+use super::{Bitmap, Image};
 
 pub struct Font {
     char_set: CharSet,
@@ -13,6 +13,11 @@ impl Font {
         }
     }
 
+    /// Unpacks every character of the font.
+    pub fn glyphs(&self) -> impl Iterator<Item = Image> + '_ {
+        (0..self.bitmap.len()).map(|x| self.bitmap.frame(x))
+    }
+
     pub fn print(&self, buffer: &mut [u32], text: &str, palette: &[u8]) {
         let mut xx = 0;
         let mut yy = 0;
@@ -68,8 +73,8 @@ pub const CHAR_SET_04: CharSet = CharSet {
     v_space: 10,
 };
 
-/*pub const CHAR_SET_06: CharSet = CharSet {
+pub const CHAR_SET_06: CharSet = CharSet {
     chars: "0123456789",
     h_space: 7,
     v_space: 10,
-};*/
+};
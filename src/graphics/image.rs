@@ -0,0 +1,93 @@
+use super::{blend, palette_color, Point, Size, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// The part of the screen drawing is limited to, the right and the bottom edges are excluded.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Clip {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Clip {
+    pub const SCREEN: Clip = Clip {
+        left: 0,
+        top: 0,
+        right: SCREEN_WIDTH as i32,
+        bottom: SCREEN_HEIGHT as i32,
+    };
+}
+
+/// A frame of a [`Bitmap`](super::Bitmap) unpacked into palette entries, `None` where it's
+/// transparent.
+pub struct Image {
+    pub size: Size,
+    pixels: Vec<Option<u8>>,
+}
+
+impl Image {
+    pub fn new(size: Size, pixels: Vec<Option<u8>>) -> Self {
+        assert_eq!(pixels.len(), (size.width * size.height) as usize);
+
+        Self { size, pixels }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Option<u8> {
+        self.pixels[(y * self.size.width + x) as usize]
+    }
+
+    /// The palette entries of the opaque pixels, as often as they're used.
+    pub fn entries(&self) -> impl Iterator<Item = u8> + '_ {
+        self.pixels.iter().flatten().copied()
+    }
+
+    /// Draws the image `scale` times its size with the top left corner at `(x, y)`, which may be
+    /// off the screen. Pixels are picked by the nearest neighbour.
+    pub fn draw_scaled(
+        &self,
+        pos: (i32, i32),
+        scale: f32,
+        clip: Clip,
+        buffer: &mut [u32],
+        palette: &[u8],
+    ) {
+        self.draw_blended(pos, scale, clip, buffer, palette, 1.0);
+    }
+
+    /// Draws the image like [`Image::draw_scaled`] mixed over the buffer, `alpha` is the share of
+    /// the image.
+    pub fn draw_blended(
+        &self,
+        (x, y): (i32, i32),
+        scale: f32,
+        clip: Clip,
+        buffer: &mut [u32],
+        palette: &[u8],
+        alpha: f32,
+    ) {
+        let width = (self.size.width as f32 * scale).round() as i32;
+        let height = (self.size.height as f32 * scale).round() as i32;
+
+        if width <= 0 || height <= 0 {
+            return;
+        }
+
+        for dy in (clip.top - y).max(0)..height.min(clip.bottom - y) {
+            let sy = ((dy as f32 / scale) as u32).min(self.size.height - 1);
+
+            for dx in (clip.left - x).max(0)..width.min(clip.right - x) {
+                let sx = ((dx as f32 / scale) as u32).min(self.size.width - 1);
+
+                if let Some(value) = self.pixel(sx, sy) {
+                    let pixel = &mut buffer[Point::xy((x + dx) as u32, (y + dy) as u32).index()];
+                    let color = palette_color(palette, value);
+
+                    *pixel = match alpha >= 1.0 {
+                        true => color,
+                        false => blend(*pixel, color, alpha as f64),
+                    };
+                }
+            }
+        }
+    }
+}
@@ -23,6 +23,28 @@ impl Canvas {
     }
 }
 
+/// Returns the screen pixel of the palette entry. Palettes hold 6-bit VGA components.
+pub fn palette_color(palette: &[u8], index: u8) -> u32 {
+    let i = index as usize * 3;
+
+    u32::from_be_bytes([
+        255,
+        palette[i] << 2,
+        palette[i + 1] << 2,
+        palette[i + 2] << 2,
+    ])
+}
+
+/// Mixes the `front` pixel over the `back` one, `alpha` is the share of the front one.
+pub fn blend(back: u32, front: u32, alpha: f64) -> u32 {
+    let back = back.to_be_bytes();
+    let front = front.to_be_bytes();
+
+    let mix = |i: usize| ((1.0 - alpha) * back[i] as f64 + alpha * front[i] as f64).round() as u8;
+
+    u32::from_be_bytes([255, mix(1), mix(2), mix(3)])
+}
+
 impl Default for Canvas {
     fn default() -> Self {
         Self::new()
@@ -84,10 +106,12 @@ impl Size {
 mod bitmap;
 pub mod font;
 mod frame;
+mod image;
 mod sprite;
 mod sprite_font;
 
 pub use self::bitmap::{decode, Bitmap};
 pub use self::frame::{Frame, FRAME_BORDER};
+pub use self::image::{Clip, Image};
 pub use self::sprite::Sprite;
 pub use self::sprite_font::SpriteFont;
@@ -0,0 +1,217 @@
+use std::rc::Rc;
+use winit::keyboard::{Key, NamedKey, SmolStr};
+
+use super::{InputDevice, InputHelper};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    SteerLeft = 0,
+    SteerRight = 1,
+    Accelerate = 2,
+    Brake = 3,
+    GearUp = 4,
+    GearDown = 5,
+    Confirm = 6,
+    Back = 7,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::SteerLeft,
+        Action::SteerRight,
+        Action::Accelerate,
+        Action::Brake,
+        Action::GearUp,
+        Action::GearDown,
+        Action::Confirm,
+        Action::Back,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SteerLeft => "STEER LEFT",
+            Self::SteerRight => "STEER RIGHT",
+            Self::Accelerate => "ACCELERATE",
+            Self::Brake => "BRAKE",
+            Self::GearUp => "GEAR UP",
+            Self::GearDown => "GEAR DOWN",
+            Self::Confirm => "CONFIRM",
+            Self::Back => "BACK",
+        }
+    }
+}
+
+/// The keys of a single player, one per action.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Bindings([Key; Action::ALL.len()]);
+
+impl Bindings {
+    pub fn player1() -> Self {
+        Self([
+            Key::Named(NamedKey::ArrowLeft),
+            Key::Named(NamedKey::ArrowRight),
+            Key::Named(NamedKey::ArrowUp),
+            Key::Named(NamedKey::ArrowDown),
+            Key::Named(NamedKey::Shift),
+            Key::Named(NamedKey::Control),
+            Key::Named(NamedKey::Enter),
+            Key::Named(NamedKey::Escape),
+        ])
+    }
+
+    pub fn player2() -> Self {
+        Self([
+            char_key('a'),
+            char_key('d'),
+            char_key('w'),
+            char_key('s'),
+            char_key('e'),
+            char_key('q'),
+            Key::Named(NamedKey::Space),
+            Key::Named(NamedKey::Tab),
+        ])
+    }
+
+    pub fn get(&self, action: Action) -> &Key {
+        &self.0[action as usize]
+    }
+
+    /// Binds the key to the action. If another action of the player already uses the key, it gets
+    /// the key which the action had before. See [`Controls::set`] for both players.
+    pub fn set(&mut self, action: Action, key: Key) {
+        if let Some(other) = self.0.iter().position(|x| *x == key) {
+            self.0[other] = self.0[action as usize].clone();
+        }
+
+        self.0[action as usize] = key;
+    }
+
+    pub fn is_down(&self, input: &InputHelper, action: Action) -> bool {
+        input.is_down(self.get(action).clone())
+    }
+
+    pub fn just_pressed(&self, input: &InputHelper, action: Action) -> bool {
+        input.just_pressed(self.get(action).clone())
+    }
+
+    /// Whether the key of the action has been pressed since the last step, OS repeats included.
+    pub fn pressed(&self, input: &InputHelper, action: Action) -> bool {
+        input.keys().any(|x| x == self.get(action))
+    }
+}
+
+/// The key bindings of both players.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Controls {
+    pub players: [Bindings; 2],
+}
+
+impl Controls {
+    pub fn player(&self, player: usize) -> &Bindings {
+        &self.players[player]
+    }
+
+    /// Binds the key to the action of the player. If an action of either player already uses the
+    /// key, it gets the key which the action had before, so no two actions ever share a key.
+    pub fn set(&mut self, player: usize, action: Action, key: Key) {
+        let old = self.players[player].get(action).clone();
+
+        for slot in self.players.iter_mut().flat_map(|x| x.0.iter_mut()) {
+            if *slot == key {
+                *slot = old.clone();
+            }
+        }
+
+        self.players[player].0[action as usize] = key;
+    }
+}
+
+/// What the menus read: the keys of player 1, OS repeats included, and the device of player 1 if
+/// one is connected. The keys of player 2 mean nothing there, so Tab and Space don't go back and
+/// confirm.
+#[derive(Clone)]
+pub struct MenuInput {
+    bindings: Bindings,
+    device: Option<Rc<dyn InputDevice>>,
+}
+
+impl MenuInput {
+    pub fn new(bindings: Bindings, device: Option<Rc<dyn InputDevice>>) -> Self {
+        Self { bindings, device }
+    }
+
+    /// Whether the action has been pressed since the last step on the keyboard or on the device.
+    pub fn pressed(&self, input: &InputHelper, action: Action) -> bool {
+        self.bindings.pressed(input, action)
+            || self.device.as_ref().is_some_and(|x| x.just_pressed(action))
+    }
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self {
+            players: [Bindings::player1(), Bindings::player2()],
+        }
+    }
+}
+
+const KEY_NAMES: &[(NamedKey, &str)] = &[
+    (NamedKey::ArrowLeft, "LEFT"),
+    (NamedKey::ArrowRight, "RIGHT"),
+    (NamedKey::ArrowUp, "UP"),
+    (NamedKey::ArrowDown, "DOWN"),
+    (NamedKey::Enter, "ENTER"),
+    (NamedKey::Escape, "ESC"),
+    (NamedKey::Space, "SPACE"),
+    (NamedKey::Tab, "TAB"),
+    (NamedKey::Backspace, "BKSP"),
+    (NamedKey::Shift, "SHIFT"),
+    (NamedKey::Control, "CTRL"),
+    (NamedKey::Alt, "ALT"),
+    (NamedKey::Insert, "INS"),
+    (NamedKey::Delete, "DEL"),
+    (NamedKey::Home, "HOME"),
+    (NamedKey::End, "END"),
+    (NamedKey::PageUp, "PGUP"),
+    (NamedKey::PageDown, "PGDN"),
+];
+
+fn char_key(c: char) -> Key {
+    Key::Character(SmolStr::new(c.to_string()))
+}
+
+/// Returns the name of the key in the characters of the menu fonts, `None` for the keys which
+/// can't be bound.
+pub fn key_name(key: &Key) -> Option<String> {
+    match key {
+        Key::Named(named) => KEY_NAMES
+            .iter()
+            .find(|(x, _)| x == named)
+            .map(|(_, name)| name.to_string()),
+        Key::Character(c) => {
+            let mut chars = c.chars();
+
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphanumeric() => {
+                    Some(c.to_ascii_uppercase().to_string())
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The reverse of [`key_name`].
+pub fn parse_key(name: &str) -> Option<Key> {
+    if let Some((key, _)) = KEY_NAMES.iter().find(|(_, x)| *x == name) {
+        return Some(Key::Named(*key));
+    }
+
+    let mut chars = name.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphanumeric() => Some(char_key(c.to_ascii_lowercase())),
+        _ => None,
+    }
+}
@@ -0,0 +1,255 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use super::{Action, Bindings, InputHelper};
+use crate::game::options::Acceleration;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Axis {
+    Steering = 0, // -1.0 is full left, 1.0 is full right
+    Pedal = 1,    // -1.0 is full brake, 1.0 is full throttle
+}
+
+/// Something a player drives with.
+pub trait InputDevice {
+    fn is_down(&self, action: Action) -> bool;
+
+    fn just_pressed(&self, action: Action) -> bool;
+
+    /// Returns the position of the axis in `-1.0..=1.0`. Digital devices derive it from the
+    /// buttons of the corresponding actions.
+    fn axis(&self, axis: Axis) -> f32 {
+        digital_axis(self, axis)
+    }
+
+    /// Takes the state of the device for the next tick. Returns `true` if anything has changed.
+    fn update(&self) -> bool {
+        false
+    }
+}
+
+fn digital_axis(device: &(impl InputDevice + ?Sized), axis: Axis) -> f32 {
+    let (neg, pos) = match axis {
+        Axis::Steering => (Action::SteerLeft, Action::SteerRight),
+        Axis::Pedal => (Action::Brake, Action::Accelerate),
+    };
+
+    device.is_down(pos) as u8 as f32 - device.is_down(neg) as u8 as f32
+}
+
+/// Converts raw axis values to `-1.0..=1.0`, ignoring the small deflections a stick never comes
+/// back from.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AnalogAxis {
+    pub min: i32,
+    pub max: i32,
+    pub dead_zone: f32,
+}
+
+impl AnalogAxis {
+    pub fn normalize(&self, raw: i32) -> f32 {
+        let center = (self.min + self.max) as f32 / 2.0;
+        let half = (self.max - self.min) as f32 / 2.0;
+        let value = ((raw as f32 - center) / half).clamp(-1.0, 1.0);
+
+        if value.abs() < self.dead_zone {
+            0.0
+        } else {
+            // rescale, so the output starts from zero right at the edge of the dead zone
+            value.signum() * (value.abs() - self.dead_zone) / (1.0 - self.dead_zone)
+        }
+    }
+}
+
+impl Default for AnalogAxis {
+    fn default() -> Self {
+        Self {
+            min: i16::MIN as i32,
+            max: i16::MAX as i32,
+            dead_zone: 0.15,
+        }
+    }
+}
+
+/// The keyboard with the bindings of one player.
+pub struct Keyboard {
+    input: Rc<RefCell<InputHelper>>,
+    bindings: Bindings,
+}
+
+impl Keyboard {
+    pub fn new(input: Rc<RefCell<InputHelper>>, bindings: Bindings) -> Self {
+        Self { input, bindings }
+    }
+}
+
+impl InputDevice for Keyboard {
+    fn is_down(&self, action: Action) -> bool {
+        self.bindings.is_down(&self.input.borrow(), action)
+    }
+
+    fn just_pressed(&self, action: Action) -> bool {
+        self.bindings.just_pressed(&self.input.borrow(), action)
+    }
+}
+
+/// Devices driven as one, e.g. the keyboard along with a pad: an action is down if it's down on
+/// any of them, an axis follows the one moved the most.
+pub struct Combined(Vec<Rc<dyn InputDevice>>);
+
+impl Combined {
+    pub fn new(devices: Vec<Rc<dyn InputDevice>>) -> Self {
+        Self(devices)
+    }
+}
+
+impl InputDevice for Combined {
+    fn is_down(&self, action: Action) -> bool {
+        self.0.iter().any(|x| x.is_down(action))
+    }
+
+    fn just_pressed(&self, action: Action) -> bool {
+        self.0.iter().any(|x| x.just_pressed(action))
+    }
+
+    fn axis(&self, axis: Axis) -> f32 {
+        self.0
+            .iter()
+            .map(|x| x.axis(axis))
+            .fold(0.0, |a, b| if b.abs() > a.abs() { b } else { a })
+    }
+}
+
+/// A device driven by code, e.g. by tests. Like a real device, it shows the presses from the
+/// next [`InputDevice::update`] on.
+#[derive(Default)]
+pub struct VirtualDevice {
+    down: Cell<u8>,
+    pressed: Cell<u8>,
+    pending: Cell<u8>,
+    axes: Cell<[Option<f32>; 2]>,
+}
+
+impl VirtualDevice {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn press(&self, action: Action) {
+        let bit = 1 << action as u8;
+
+        if self.down.get() & bit == 0 {
+            self.pending.set(self.pending.get() | bit);
+        }
+
+        self.down.set(self.down.get() | bit);
+    }
+
+    pub fn release(&self, action: Action) {
+        self.down.set(self.down.get() & !(1 << action as u8));
+    }
+
+    /// Sets the axis to the value, `None` makes it follow the buttons again.
+    pub fn set_axis(&self, axis: Axis, value: Option<f32>) {
+        let mut axes = self.axes.get();
+        axes[axis as usize] = value.map(|x| x.clamp(-1.0, 1.0));
+
+        self.axes.set(axes);
+    }
+}
+
+impl InputDevice for VirtualDevice {
+    fn is_down(&self, action: Action) -> bool {
+        self.down.get() & (1 << action as u8) != 0
+    }
+
+    fn just_pressed(&self, action: Action) -> bool {
+        self.pressed.get() & (1 << action as u8) != 0
+    }
+
+    fn axis(&self, axis: Axis) -> f32 {
+        match self.axes.get()[axis as usize] {
+            Some(value) => value,
+            None => digital_axis(self, axis),
+        }
+    }
+
+    fn update(&self) -> bool {
+        let pressed = self.pending.replace(0);
+
+        self.pressed.replace(pressed) != pressed
+    }
+}
+
+/// The devices connected besides the keyboard, shared by the engine which updates them and the
+/// game which reads them.
+#[derive(Clone, Default)]
+pub struct Devices(Rc<RefCell<Vec<Rc<dyn InputDevice>>>>);
+
+impl Devices {
+    pub fn connect(&self, device: Rc<dyn InputDevice>) {
+        self.0.borrow_mut().push(device);
+    }
+
+    pub fn get(&self, index: usize) -> Option<Rc<dyn InputDevice>> {
+        self.0.borrow().get(index).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    /// Updates every device, returns `true` if any of them has changed.
+    pub fn update(&self) -> bool {
+        let mut changed = false;
+
+        for device in self.0.borrow().iter() {
+            changed |= device.update();
+        }
+
+        changed
+    }
+}
+
+/// What a player does with the car during a step.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct DriverInput {
+    pub steer: f32,    // -1.0..=1.0
+    pub throttle: f32, // 0.0..=1.0
+    pub brake: f32,    // 0.0..=1.0
+    pub gear_up: bool,
+    pub gear_down: bool,
+}
+
+impl DriverInput {
+    /// Reads the device. With [`Acceleration::Button`] the pedals are the Accelerate and Brake
+    /// buttons, with [`Acceleration::Joystick`] it's the pedal axis: push to accelerate, pull to
+    /// brake.
+    pub fn read(device: &dyn InputDevice, accel: Acceleration) -> Self {
+        let (throttle, brake) = match accel {
+            Acceleration::Button => (
+                device.is_down(Action::Accelerate) as u8 as f32,
+                device.is_down(Action::Brake) as u8 as f32,
+            ),
+            Acceleration::Joystick => {
+                let pedal = device.axis(Axis::Pedal);
+
+                (pedal.max(0.0), (-pedal).max(0.0))
+            }
+        };
+
+        Self {
+            steer: device.axis(Axis::Steering),
+            throttle,
+            brake,
+            gear_up: device.just_pressed(Action::GearUp),
+            gear_down: device.just_pressed(Action::GearDown),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    cell::Cell,
+    sync::{Arc, Mutex},
+};
+
+use super::{Action, AnalogAxis, Axis, InputDevice};
+
+const MAX_AXES: usize = 8;
+
+// the layout of an XInput pad as reported by the Linux joystick driver
+const AXIS_STICK_X: usize = 0;
+const AXIS_STICK_Y: usize = 1;
+const AXIS_DPAD_X: usize = 6;
+
+const BUTTONS: [Option<u8>; Action::ALL.len()] = [
+    None,    // SteerLeft, the stick or the d-pad
+    None,    // SteerRight, the stick or the d-pad
+    Some(0), // Accelerate, A
+    Some(1), // Brake, B
+    Some(5), // GearUp, right bumper
+    Some(4), // GearDown, left bumper
+    Some(7), // Confirm, Start
+    Some(6), // Back, Back
+];
+
+/// How far the stick has to go to count as pressing a direction.
+const STICK_THRESHOLD: f32 = 0.5;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+struct Snapshot {
+    buttons: u32,
+    axes: [i16; MAX_AXES],
+}
+
+/// A game controller. The events are read by a background thread, every step sees the state of
+/// the pad at the time of [`InputDevice::update`].
+pub struct Gamepad {
+    shared: Arc<Mutex<Snapshot>>,
+    current: Cell<Snapshot>,
+    previous: Cell<Snapshot>,
+    analog: AnalogAxis,
+}
+
+impl Gamepad {
+    /// Opens every connected pad, in the order the system numbers them.
+    pub fn open_all() -> Vec<Gamepad> {
+        (0..4).filter_map(|n| Self::open(n).ok()).collect()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn open(n: usize) -> std::io::Result<Self> {
+        use std::{fs::File, io::Read, thread};
+
+        const JS_EVENT_BUTTON: u8 = 0x01;
+        const JS_EVENT_AXIS: u8 = 0x02;
+        const JS_EVENT_INIT: u8 = 0x80;
+
+        let mut file = File::open(format!("/dev/input/js{n}"))?;
+        let shared = Arc::new(Mutex::new(Snapshot::default()));
+
+        {
+            let shared = Arc::clone(&shared);
+
+            thread::spawn(move || {
+                // struct js_event { u32 time; i16 value; u8 type; u8 number; }
+                let mut event = [0; 8];
+
+                while file.read_exact(&mut event).is_ok() {
+                    let value = i16::from_le_bytes([event[4], event[5]]);
+                    let number = event[7] as usize;
+                    let mut state = shared.lock().unwrap();
+
+                    match event[6] & !JS_EVENT_INIT {
+                        JS_EVENT_BUTTON if number < 32 => {
+                            if value != 0 {
+                                state.buttons |= 1 << number;
+                            } else {
+                                state.buttons &= !(1 << number);
+                            }
+                        }
+                        JS_EVENT_AXIS if number < MAX_AXES => state.axes[number] = value,
+                        _ => {}
+                    }
+                }
+
+                // unplugged, let go of everything
+                *shared.lock().unwrap() = Snapshot::default();
+            });
+        }
+
+        Ok(Self {
+            shared,
+            current: Cell::default(),
+            previous: Cell::default(),
+            analog: AnalogAxis::default(),
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open(_n: usize) -> std::io::Result<Self> {
+        Err(std::io::ErrorKind::Unsupported.into())
+    }
+
+    fn button(snapshot: Snapshot, action: Action) -> bool {
+        BUTTONS[action as usize].is_some_and(|x| snapshot.buttons & (1 << x) != 0)
+    }
+
+    fn steering(&self, snapshot: Snapshot) -> f32 {
+        match snapshot.axes[AXIS_DPAD_X] {
+            0 => self.analog.normalize(snapshot.axes[AXIS_STICK_X] as i32),
+            dpad => dpad.signum() as f32,
+        }
+    }
+
+    fn is_down_in(&self, snapshot: Snapshot, action: Action) -> bool {
+        match action {
+            Action::SteerLeft => self.steering(snapshot) <= -STICK_THRESHOLD,
+            Action::SteerRight => self.steering(snapshot) >= STICK_THRESHOLD,
+            _ => Self::button(snapshot, action),
+        }
+    }
+}
+
+impl InputDevice for Gamepad {
+    fn is_down(&self, action: Action) -> bool {
+        self.is_down_in(self.current.get(), action)
+    }
+
+    fn just_pressed(&self, action: Action) -> bool {
+        self.is_down(action) && !self.is_down_in(self.previous.get(), action)
+    }
+
+    fn axis(&self, axis: Axis) -> f32 {
+        let snapshot = self.current.get();
+
+        match axis {
+            Axis::Steering => self.steering(snapshot),
+            // the stick is pushed away to accelerate, which the driver reports as negative
+            Axis::Pedal => -self.analog.normalize(snapshot.axes[AXIS_STICK_Y] as i32),
+        }
+    }
+
+    fn update(&self) -> bool {
+        let snapshot = *self.shared.lock().unwrap();
+
+        self.previous.set(self.current.replace(snapshot));
+
+        self.previous.get() != snapshot
+    }
+}
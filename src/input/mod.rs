@@ -0,0 +1,226 @@
+use std::{collections::HashMap, time::Duration};
+use winit::{
+    event::{ElementState, KeyEvent},
+    keyboard::{Key, NamedKey},
+    platform::modifier_supplement::KeyEventExtModifierSupplement,
+};
+
+mod controls;
+mod device;
+mod gamepad;
+mod record;
+
+pub use controls::{key_name, parse_key, Action, Bindings, Controls, MenuInput};
+pub use device::{
+    AnalogAxis, Axis, Combined, Devices, DriverInput, InputDevice, Keyboard, VirtualDevice,
+};
+pub use gamepad::Gamepad;
+pub use record::{InputSession, RecordedEvent, Recorder, Recording};
+
+pub const ENTER_CHAR: char = '\r';
+pub const BACKSPACE_CHAR: char = '\x08';
+pub const ESCAPE_CHAR: char = '\x1b';
+
+/// A keyboard event stripped of everything the game doesn't use.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct InputEvent {
+    pub key: Key,
+    pub state: ElementState,
+    /// Set for the events which are generated by the OS while the key is held.
+    pub repeat: bool,
+}
+
+impl From<KeyEvent> for InputEvent {
+    fn from(event: KeyEvent) -> Self {
+        Self {
+            key: event.key_without_modifiers(),
+            state: event.state,
+            repeat: event.repeat,
+        }
+    }
+}
+
+/// The delay before a held key starts repeating and the interval between the repeats.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RepeatRate {
+    pub delay: Duration,
+    pub interval: Duration,
+}
+
+impl RepeatRate {
+    /// The number of repeats of a key which has been held for `time`.
+    fn repeats(&self, time: Duration) -> u128 {
+        match time.checked_sub(self.delay) {
+            Some(time) => 1 + time.as_nanos() / self.interval.as_nanos().max(1),
+            None => 0,
+        }
+    }
+}
+
+impl Default for RepeatRate {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(250),
+            interval: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Collects the keyboard events until a tick has seen them, the engine clears them after every
+/// tick.
+///
+/// `keys()` and `chars()` return every press of the batch including the OS repeats, which suits
+/// typing. The `is_down()`, `just_pressed()` and `just_released()` functions track the physical
+/// state of the keys, which suits driving.
+pub struct InputHelper {
+    keys: Vec<Key>,
+    pressed: Vec<Key>,
+    released: Vec<Key>,
+    repeated: Vec<Key>,
+    held: HashMap<Key, Duration>,
+    repeat_rate: RepeatRate,
+    now: Duration,
+}
+
+impl InputHelper {
+    pub fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            pressed: Vec::new(),
+            released: Vec::new(),
+            repeated: Vec::new(),
+            held: HashMap::new(),
+            repeat_rate: RepeatRate::default(),
+            now: Duration::ZERO,
+        }
+    }
+
+    pub fn with_repeat_rate(mut self, repeat_rate: RepeatRate) -> Self {
+        self.repeat_rate = repeat_rate;
+        self
+    }
+
+    pub fn handle(&mut self, event: KeyEvent) {
+        self.handle_event(event.into());
+    }
+
+    pub fn handle_event(&mut self, event: InputEvent) {
+        match event.state {
+            ElementState::Pressed => {
+                if !event.repeat && !self.held.contains_key(&event.key) {
+                    self.held.insert(event.key.clone(), self.now);
+                    self.pressed.push(event.key.clone());
+                }
+
+                self.keys.push(event.key);
+            }
+            ElementState::Released => {
+                if self.held.remove(&event.key).is_some() {
+                    self.released.push(event.key);
+                }
+            }
+        }
+    }
+
+    /// Releases every held key, e.g. when the window loses focus and the releases won't come.
+    pub fn release_all(&mut self) {
+        self.released.extend(self.held.drain().map(|(key, _)| key));
+    }
+
+    /// Moves the time of the helper to `now` and fires the repeats of the held keys which are due
+    /// by then. Called by the engine before every step.
+    pub fn update(&mut self, now: Duration) {
+        let rate = self.repeat_rate;
+        let prev = self.now;
+
+        for (key, since) in &self.held {
+            if rate.repeats(now.saturating_sub(*since)) > rate.repeats(prev.saturating_sub(*since))
+            {
+                self.repeated.push(key.clone());
+            }
+        }
+
+        self.now = now;
+    }
+
+    /// The time of the next repeat of any held key.
+    pub fn next_repeat(&self) -> Option<Duration> {
+        let rate = self.repeat_rate;
+
+        self.held
+            .values()
+            .map(|since| {
+                // the n-th repeat comes at `since + delay + (n - 1) * interval`
+                let count = rate.repeats(self.now.saturating_sub(*since));
+
+                *since + rate.delay + rate.interval * count as u32
+            })
+            .min()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.pressed.clear();
+        self.released.clear();
+        self.repeated.clear();
+    }
+
+    /// Whether anything has happened since the last `clear()`.
+    pub fn has_events(&self) -> bool {
+        !(self.keys.is_empty()
+            && self.pressed.is_empty()
+            && self.released.is_empty()
+            && self.repeated.is_empty())
+    }
+
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.keys
+            .iter()
+            .filter_map(|x| x.to_text())
+            .flat_map(|x| x.chars())
+    }
+
+    /// The keys which are held down.
+    pub fn held(&self) -> impl Iterator<Item = &Key> {
+        self.held.keys()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Key> {
+        self.keys.iter()
+    }
+
+    pub fn key_pressed(&self, key: NamedKey) -> bool {
+        self.keys.contains(&Key::Named(key))
+    }
+
+    pub fn is_down(&self, key: impl Into<Key>) -> bool {
+        self.held.contains_key(&key.into())
+    }
+
+    /// Whether any key went down since the last step.
+    pub fn any_pressed(&self) -> bool {
+        !self.pressed.is_empty()
+    }
+
+    pub fn just_pressed(&self, key: impl Into<Key>) -> bool {
+        self.pressed.contains(&key.into())
+    }
+
+    pub fn just_released(&self, key: impl Into<Key>) -> bool {
+        self.released.contains(&key.into())
+    }
+
+    /// Whether the key went down or has been held long enough to repeat, at the helper's own
+    /// repeat rate rather than the OS one.
+    pub fn repeating(&self, key: impl Into<Key>) -> bool {
+        let key = key.into();
+
+        self.pressed.contains(&key) || self.repeated.contains(&key)
+    }
+}
+
+impl Default for InputHelper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
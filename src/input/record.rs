@@ -0,0 +1,308 @@
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use std::{
+    collections::VecDeque,
+    fmt,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    mem,
+    path::Path,
+    time::Duration,
+};
+use winit::{event::ElementState, keyboard::Key};
+
+use super::{key_name, parse_key, InputEvent};
+use crate::game::options::Config;
+
+const HEADER: &str = "LOTUS3 RECORDING 1";
+const CONFIG_SECTION: &str = "[config]";
+const EVENTS_SECTION: &str = "[events]";
+
+/// An input event and the number of the engine step which has seen it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RecordedEvent {
+    pub step: u64,
+    pub event: InputEvent,
+}
+
+/// Everything needed to replay a run: the seed of the RNG, the clock step, the options and the
+/// keyboard events.
+///
+/// The text format has a header, the seed and the step, the `[config]` section in the format of
+/// [`Config::entries`] and the `[events]` section with a `<step> <press|release|repeat> <key>`
+/// line per event. Keys are written by [`key_name`], other characters with a `"` in front. The
+/// keys the game never looks at (function keys and such) aren't recorded.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Recording {
+    pub seed: u64,
+    pub step: Duration,
+    pub cfg: Config,
+    pub events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    pub fn new(seed: u64, step: Duration, cfg: Config) -> Self {
+        Self {
+            seed,
+            step,
+            cfg,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read '{}'!", path.display()))?;
+
+        Self::parse(&text).with_context(|| format!("Failed to parse '{}'!", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines();
+
+        ensure!(lines.next() == Some(HEADER), "Not a recording!");
+
+        let mut seed = None;
+        let mut step = None;
+
+        for line in lines.by_ref() {
+            match line.split_once('=') {
+                Some(("seed", value)) => seed = Some(value.parse()?),
+                Some(("step_us", value)) => step = Some(Duration::from_micros(value.parse()?)),
+                _ if line == CONFIG_SECTION => break,
+                _ => bail!("Invalid line '{line}'!"),
+            }
+        }
+
+        let config = lines
+            .by_ref()
+            .take_while(|x| *x != EVENTS_SECTION)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut recording = Self::new(
+            seed.context("The seed is missing!")?,
+            step.context("The step is missing!")?,
+            Config::parse(&config)?,
+        );
+
+        for line in lines.filter(|x| !x.is_empty()) {
+            let event = parse_event(line).ok_or_else(|| anyhow!("Invalid event '{line}'!"))?;
+
+            ensure!(
+                recording.events.last().is_none_or(|x| x.step <= event.step),
+                "Events are out of order at '{line}'!"
+            );
+
+            recording.events.push(event);
+        }
+
+        Ok(recording)
+    }
+
+    fn write_header(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        writeln!(f, "seed={}", self.seed)?;
+        writeln!(f, "step_us={}", self.step.as_micros())?;
+        writeln!(f, "{CONFIG_SECTION}")?;
+        write!(f, "{}", self.cfg)?;
+        writeln!(f, "{EVENTS_SECTION}")
+    }
+}
+
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_header(f)?;
+
+        for event in &self.events {
+            if let Some(line) = format_event(event) {
+                writeln!(f, "{line}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a recording as the run goes, so it survives the game being closed at any moment.
+pub struct Recorder<W: Write> {
+    out: W,
+}
+
+impl Recorder<BufWriter<File>> {
+    pub fn create(path: &Path, header: &Recording) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create '{}'!", path.display()))?;
+
+        Self::new(BufWriter::new(file), header)
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// Writes everything of the recording but its events.
+    pub fn new(mut out: W, header: &Recording) -> Result<Self> {
+        let mut text = String::new();
+        header.write_header(&mut text)?;
+
+        out.write_all(text.as_bytes())?;
+
+        Ok(Self { out })
+    }
+
+    pub fn record(&mut self, step: u64, events: &[InputEvent]) -> Result<()> {
+        for event in events {
+            let event = RecordedEvent {
+                step,
+                event: event.clone(),
+            };
+
+            if let Some(line) = format_event(&event) {
+                writeln!(self.out, "{line}")?;
+            }
+        }
+
+        if !events.is_empty() {
+            self.out.flush()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+fn format_event(event: &RecordedEvent) -> Option<String> {
+    let state = match (event.event.state, event.event.repeat) {
+        (ElementState::Pressed, false) => "press",
+        (ElementState::Pressed, true) => "repeat",
+        (ElementState::Released, _) => "release",
+    };
+
+    let key = match &event.event.key {
+        Key::Character(c) if key_name(&event.event.key).is_none() => {
+            (!c.chars().any(char::is_whitespace)).then(|| format!("\"{c}"))?
+        }
+        key => key_name(key)?,
+    };
+
+    Some(format!("{} {state} {key}", event.step))
+}
+
+fn parse_event(line: &str) -> Option<RecordedEvent> {
+    let mut parts = line.splitn(3, ' ');
+
+    let step = parts.next()?.parse().ok()?;
+
+    let (state, repeat) = match parts.next()? {
+        "press" => (ElementState::Pressed, false),
+        "repeat" => (ElementState::Pressed, true),
+        "release" => (ElementState::Released, false),
+        _ => return None,
+    };
+
+    let key = match parts.next()? {
+        key if key.starts_with('"') && key.len() > 1 => Key::Character(key[1..].into()),
+        key => parse_key(key)?,
+    };
+
+    Some(RecordedEvent {
+        step,
+        event: InputEvent { key, state, repeat },
+    })
+}
+
+/// Lets the game replay and record parts of the run, e.g. the demos. The engine passes every step's
+/// keyboard events through it.
+///
+/// Unlike the recordings of the whole run, the events are placed by time: the step of an event is
+/// a multiple of the recording's step since the start of the session.
+#[derive(Default)]
+pub struct InputSession {
+    playback: VecDeque<RecordedEvent>,
+    playing: bool,
+    interrupted: bool,
+    ended: bool,
+    recording: Option<Vec<RecordedEvent>>,
+    step: Duration,
+    start: Duration,
+}
+
+impl InputSession {
+    /// Replaces the keyboard with the events of the recording from `now` on.
+    pub fn play(&mut self, recording: &Recording, now: Duration) {
+        self.playback = recording.events.iter().cloned().collect();
+        self.playing = true;
+        self.interrupted = false;
+        self.step = recording.step;
+        self.start = now;
+    }
+
+    pub fn stop(&mut self) {
+        if self.playing {
+            self.playback.clear();
+            self.playing = false;
+            self.ended = true;
+        }
+    }
+
+    /// Whether a playback is on, it ends by itself when the events run out.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Whether a key has been pressed on the real keyboard since the playback has started.
+    pub fn interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    /// Starts recording the events from `now` on, placed at multiples of `step`.
+    pub fn record(&mut self, step: Duration, now: Duration) {
+        self.recording = Some(Vec::new());
+        self.step = step;
+        self.start = now;
+    }
+
+    /// Stops the recording and returns its events.
+    pub fn take_recording(&mut self) -> Vec<RecordedEvent> {
+        self.recording.take().unwrap_or_default()
+    }
+
+    /// Returns the events the game sees instead of the `live` ones at `now`, and whether a playback
+    /// has just ended, so the keys it holds have to be released.
+    pub(crate) fn feed(&mut self, now: Duration, live: Vec<InputEvent>) -> (Vec<InputEvent>, bool) {
+        if !self.playing {
+            if let Some(recording) = &mut self.recording {
+                let step = (now.saturating_sub(self.start).as_nanos() / self.step.as_nanos().max(1))
+                    as u64;
+
+                recording.extend(live.iter().map(|event| RecordedEvent {
+                    step,
+                    event: event.clone(),
+                }));
+            }
+
+            return (live, mem::take(&mut self.ended));
+        }
+
+        self.interrupted |= live
+            .iter()
+            .any(|x| x.state == ElementState::Pressed && !x.repeat);
+
+        let mut events = Vec::new();
+
+        while let Some(x) = self.playback.front() {
+            if self.start + self.step * x.step as u32 > now {
+                break;
+            }
+
+            events.extend(self.playback.pop_front().map(|x| x.event));
+        }
+
+        if self.playback.is_empty() {
+            self.stop();
+        }
+
+        (events, false)
+    }
+}
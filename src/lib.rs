@@ -1,4 +1,15 @@
 pub const ARCHIVE_FILE_NAME: &str = "lotus.dat";
+/// The demos of the attract mode. None ship with the game, the demos of the original aren't
+/// read, so there are only the ones recorded with `--record-demo`.
+pub const DEMOS_DIR: &str = "demos";
 
+pub mod app;
+pub mod cli;
 pub mod data;
+pub mod engine;
+pub mod game;
 pub mod graphics;
+pub mod input;
+pub mod rng;
+pub mod screen;
+pub mod task;
@@ -1,20 +1,108 @@
-use crate::{app::Application, data::Archive, engine::GameEngine, game::options::Config};
+use std::{env, process};
 
-mod app;
-mod data;
-mod engine;
-mod game;
-mod graphics;
-mod input;
-mod screen;
-mod task;
+use lotus3::{
+    app::{run_headless, Application},
+    cli::{Options, USAGE},
+    data::Archive,
+    engine::GameEngine,
+    game::{
+        self,
+        code_wheel::{CodeWheel, CODE_WHEEL_FILE},
+        options::Config,
+        race::ghost,
+        scores, settings,
+    },
+    input::{Gamepad, Recording},
+    task::{Clock, TICK},
+};
 
 fn main() -> anyhow::Result<()> {
-    let arc = Archive::open(&lotus3::ARCHIVE_FILE_NAME)?;
-    let cfg = Config::new();
-    let app = Application::new("Lotus III: The Ultimate Challenge")?;
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
 
-    let mut game = GameEngine::new(arc, cfg, app.input(), game::main)?;
+    if options.help {
+        print!("{USAGE}");
+        return Ok(());
+    }
 
-    app.run(move |ctx, signal| game.step(ctx, signal))
+    let arc = Archive::open(&options.data)?;
+
+    let path = (!options.no_settings)
+        .then(settings::settings_path)
+        .flatten();
+
+    let mut cfg = match &path {
+        Some(path) => settings::load(path)?,
+        None => Config::new(),
+    };
+
+    for (key, value) in &options.overrides {
+        cfg.set(key, value)?;
+    }
+
+    // recorded runs are replayed step by step, so they need steps of the same length
+    let clock = match options.record {
+        Some(_) => Clock::fixed_step(TICK),
+        None => Clock::real_time(),
+    };
+
+    let mut game = GameEngine::new(arc, cfg, clock, game::main)?.with_launch(options.launch);
+
+    // the overrides and replayed options are for the run only, they aren't saved
+    if let Some(path) = path.filter(|_| options.overrides.is_empty() && options.replay.is_none()) {
+        game = game.with_settings(path);
+    }
+
+    // the scores and the ghosts live next to the settings, replayed races don't enter them
+    let scores_path = (!options.no_settings)
+        .then(scores::scores_path)
+        .flatten()
+        .filter(|_| options.replay.is_none());
+
+    if let Some(path) = scores_path {
+        game = game.with_scores(scores::load(&path)?, path);
+
+        if let Some(dir) = ghost::ghosts_dir() {
+            game = game.with_ghosts(dir);
+        }
+    }
+
+    // the codes of the wheel are transcribed by its owners, they aren't part of the game data
+    let wheel_path = options.data.with_file_name(CODE_WHEEL_FILE);
+
+    if wheel_path.exists() {
+        game = game.with_code_wheel(CodeWheel::load(&wheel_path)?);
+    }
+
+    if options.record_demo {
+        game = game.with_demo_recording();
+    }
+
+    game = match (&options.record, &options.replay) {
+        (Some(path), _) => game.with_recorder(path),
+        (_, Some(path)) => game.with_playback(Recording::load(path)?),
+        // the devices aren't recorded, only the keyboard drives the recorded runs
+        _ => game.with_devices(Gamepad::open_all()),
+    };
+
+    if options.headless {
+        return run_headless(game);
+    }
+
+    let mut app = Application::new("Lotus III: The Ultimate Challenge")?;
+
+    if let Some(scale) = options.scale {
+        app = app.with_scale(scale);
+    }
+
+    if options.fullscreen {
+        app = app.with_fullscreen();
+    }
+
+    app.run(game)
 }
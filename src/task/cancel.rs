@@ -0,0 +1,159 @@
+use std::{
+    cell::{Cell, RefCell},
+    error::Error,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CancelReason {
+    /// The player has skipped the sequence.
+    Skipped,
+    /// The game is being closed.
+    Shutdown,
+}
+
+/// The error of a future which has been cancelled by a [`CancellationToken`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cancelled(pub CancelReason);
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancelled ({:?})", self.0)
+    }
+}
+
+impl Error for Cancelled {}
+
+#[derive(Default)]
+struct TokenState {
+    reason: Cell<Option<CancelReason>>,
+    wakers: RefCell<Vec<Waker>>,
+    children: RefCell<Vec<Weak<TokenState>>>,
+}
+
+impl TokenState {
+    fn cancel(&self, reason: CancelReason) {
+        if self.reason.get().is_some() {
+            return;
+        }
+
+        self.reason.set(Some(reason));
+
+        for waker in self.wakers.take() {
+            waker.wake();
+        }
+
+        for child in self.children.take() {
+            if let Some(child) = child.upgrade() {
+                child.cancel(reason);
+            }
+        }
+    }
+
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.borrow_mut();
+
+        if !wakers.iter().any(|x| x.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+}
+
+/// A shared flag which cancels every future run under it (or under any of its children) at once.
+#[derive(Clone, Default)]
+pub struct CancellationToken(Rc<TokenState>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token which is cancelled together with this one, but can be cancelled on its own
+    /// without affecting the parent.
+    pub fn child(&self) -> Self {
+        let child = Self::new();
+
+        match self.reason() {
+            Some(reason) => child.cancel(reason),
+            None => self.0.children.borrow_mut().push(Rc::downgrade(&child.0)),
+        }
+
+        child
+    }
+
+    /// Cancels the token and its children. Only the first reason is kept.
+    pub fn cancel(&self, reason: CancelReason) {
+        self.0.cancel(reason);
+    }
+
+    pub fn reason(&self) -> Option<CancelReason> {
+        self.0.reason.get()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.reason().is_some()
+    }
+
+    /// Resolves once the token is cancelled.
+    pub fn cancelled(&self) -> WaitForCancellation {
+        WaitForCancellation(self.clone())
+    }
+
+    /// Runs the future until it's done or the token is cancelled, whichever comes first. The
+    /// future is not polled anymore after the cancellation.
+    pub fn run<F: Future>(&self, future: F) -> WithCancel<F> {
+        WithCancel {
+            token: self.clone(),
+            future: Box::pin(future),
+        }
+    }
+}
+
+pub struct WaitForCancellation(CancellationToken);
+
+impl Future for WaitForCancellation {
+    type Output = CancelReason;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.0.reason() {
+            Some(reason) => Poll::Ready(reason),
+            None => {
+                self.0 .0.register(cx.waker());
+
+                Poll::Pending
+            }
+        }
+    }
+}
+
+pub struct WithCancel<F> {
+    token: CancellationToken,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for WithCancel<F> {
+    type Output = Result<F::Output, Cancelled>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(reason) = self.token.reason() {
+            return Poll::Ready(Err(Cancelled(reason)));
+        }
+
+        if let Poll::Ready(output) = self.future.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+
+        // the future might have cancelled its own token
+        if let Some(reason) = self.token.reason() {
+            return Poll::Ready(Err(Cancelled(reason)));
+        }
+
+        self.token.0.register(cx.waker());
+
+        Poll::Pending
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClockMode {
+    /// Follows the wall clock.
+    RealTime,
+    /// Moves by the same amount of time on every executor run, however long it actually takes.
+    FixedStep(Duration),
+    /// Moves only by [`Clock::advance`].
+    Manual,
+}
+
+/// The time source of the executor. Timers and fades measure time as a `Duration` since the clock
+/// has been started, so the same run can be replayed with a virtual time.
+pub struct Clock {
+    mode: ClockMode,
+    start: Instant,
+    now: Cell<Duration>,
+}
+
+impl Clock {
+    pub fn new(mode: ClockMode) -> Self {
+        Self {
+            mode,
+            start: Instant::now(),
+            now: Cell::new(Duration::ZERO),
+        }
+    }
+
+    pub fn real_time() -> Self {
+        Self::new(ClockMode::RealTime)
+    }
+
+    pub fn fixed_step(step: Duration) -> Self {
+        Self::new(ClockMode::FixedStep(step))
+    }
+
+    pub fn manual() -> Self {
+        Self::new(ClockMode::Manual)
+    }
+
+    pub fn mode(&self) -> ClockMode {
+        self.mode
+    }
+
+    pub fn now(&self) -> Duration {
+        self.now.get()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    /// Called by the executor before every run.
+    pub(super) fn tick(&self) {
+        match self.mode {
+            ClockMode::RealTime => self.now.set(self.start.elapsed()),
+            ClockMode::FixedStep(step) => self.advance(step),
+            ClockMode::Manual => {}
+        }
+    }
+
+    /// Converts the clock time into the wall clock time of the run which reaches it, `None` if
+    /// the clock doesn't follow the wall clock.
+    pub fn to_instant(&self, time: Duration) -> Option<Instant> {
+        match self.mode {
+            ClockMode::RealTime => Some(self.start + time),
+            // every run moves the clock by one step only, so the runs are paced at the step rate
+            ClockMode::FixedStep(step) => Some(self.start + self.now() + step),
+            ClockMode::Manual => None,
+        }
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::real_time()
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    time::{Duration, Instant},
+};
+
+use super::{Clock, Stats, Ticker, TimerWheel};
+
+type LocalTask = Pin<Box<dyn Future<Output = ()>>>;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Rc<Inner>>> = const { RefCell::new(None) };
+}
+
+/// Ids of the woken tasks. Wakers have to be `Send + Sync`, so this is the only part of the executor
+/// which is shared through an `Arc`, the tasks themselves never leave the thread.
+#[derive(Default)]
+struct Queue {
+    ready: Mutex<VecDeque<usize>>,
+}
+
+struct TaskWaker {
+    id: usize,
+    queue: Arc<Queue>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.queue.ready.lock().unwrap().push_back(self.id);
+    }
+}
+
+struct Inner {
+    tasks: RefCell<HashMap<usize, (LocalTask, Waker)>>,
+    timers: Rc<RefCell<TimerWheel>>,
+    ticker: Rc<RefCell<Ticker>>,
+    clock: Clock,
+    queue: Arc<Queue>,
+    next_id: Cell<usize>,
+    running: Cell<Option<usize>>,
+    aborted: Cell<bool>,
+}
+
+impl Inner {
+    fn spawn<F>(self: &Rc<Self>, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+
+        let state = Rc::new(RefCell::new(JoinState {
+            output: None,
+            waker: None,
+            finished: false,
+            aborted: false,
+        }));
+
+        let task = {
+            let state = Rc::clone(&state);
+
+            async move {
+                let output = future.await;
+                let mut state = state.borrow_mut();
+
+                state.output = Some(output);
+                state.finished = true;
+
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            }
+        };
+
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id,
+            queue: Arc::clone(&self.queue),
+        }));
+
+        waker.wake_by_ref();
+
+        self.tasks.borrow_mut().insert(id, (Box::pin(task), waker));
+
+        JoinHandle {
+            id,
+            state,
+            executor: Rc::downgrade(self),
+        }
+    }
+
+    fn abort(&self, id: usize) {
+        let task = self.tasks.borrow_mut().remove(&id);
+
+        if task.is_none() && self.running.get() == Some(id) {
+            // the task aborts itself, it'll be dropped as soon as its poll returns
+            self.aborted.set(true);
+        }
+    }
+}
+
+/// A single-threaded executor. Tasks are polled only when woken, in the order they were woken.
+#[derive(Clone)]
+pub struct Executor(Rc<Inner>);
+
+impl Executor {
+    pub fn new() -> Self {
+        Self::with_clock(Clock::real_time())
+    }
+
+    pub fn with_clock(clock: Clock) -> Self {
+        Self(Rc::new(Inner {
+            tasks: RefCell::new(HashMap::new()),
+            timers: Rc::new(RefCell::new(TimerWheel::default())),
+            ticker: Rc::new(RefCell::new(Ticker::default())),
+            clock,
+            queue: Arc::new(Queue::default()),
+            next_id: Cell::new(0),
+            running: Cell::new(None),
+            aborted: Cell::new(false),
+        }))
+    }
+
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        self.0.spawn(future)
+    }
+
+    /// Polls every task that was woken before this call or whose timer has expired, then does the
+    /// same once per fixed tick which is due. Tasks woken by the last pass (including those which
+    /// are spawned) are polled by the next call.
+    pub fn run(&self) {
+        self.run_with(|| {});
+    }
+
+    /// Does what [`Executor::run`] does, calling `on_tick` right before every tick is issued, e.g.
+    /// to take the input of that tick.
+    pub fn run_with(&self, mut on_tick: impl FnMut()) {
+        let started = Instant::now();
+        let prev = CURRENT.replace(Some(Rc::clone(&self.0)));
+
+        self.0.clock.tick();
+
+        let now = self.0.clock.now();
+        self.0.timers.borrow_mut().expire(now);
+
+        let ticks = self.0.ticker.borrow_mut().advance(now);
+
+        if ticks == 0 {
+            self.poll_ready();
+        }
+
+        for _ in 0..ticks {
+            on_tick();
+
+            self.0.ticker.borrow_mut().fire();
+            self.poll_ready();
+        }
+
+        CURRENT.set(prev);
+
+        self.0
+            .ticker
+            .borrow_mut()
+            .update_stats(now, started.elapsed());
+    }
+
+    fn poll_ready(&self) {
+        let mut ready = Vec::new();
+
+        for id in self.0.queue.ready.lock().unwrap().drain(..) {
+            if !ready.contains(&id) {
+                ready.push(id);
+            }
+        }
+
+        for id in ready {
+            let Some((mut task, waker)) = self.0.tasks.borrow_mut().remove(&id) else {
+                continue; // finished or aborted
+            };
+
+            self.0.running.set(Some(id));
+
+            let poll = task.as_mut().poll(&mut Context::from_waker(&waker));
+
+            self.0.running.set(None);
+
+            if poll.is_pending() && !self.0.aborted.replace(false) {
+                self.0.tasks.borrow_mut().insert(id, (task, waker));
+            }
+        }
+    }
+
+    /// Wakes every task, e.g. to let them react to an event they have no waker for.
+    pub fn wake_all(&self) {
+        for (_, waker) in self.0.tasks.borrow().values() {
+            waker.wake_by_ref();
+        }
+    }
+
+    /// Returns the time when [`Executor::run`] has anything to do: now if some task is already
+    /// woken, the earliest timer deadline or awaited tick otherwise, or `None` if every task waits
+    /// for something else.
+    pub fn next_wakeup(&self) -> Option<Instant> {
+        let clock = &self.0.clock;
+
+        if !self.0.queue.ready.lock().unwrap().is_empty() {
+            return clock
+                .to_instant(clock.now())
+                .or_else(|| Some(Instant::now()));
+        }
+
+        let ticker = self.0.ticker.borrow();
+
+        let timer = self.0.timers.borrow().next_deadline();
+        let tick = ticker.is_waited().then(|| ticker.next_tick());
+
+        clock.to_instant(timer.into_iter().chain(tick).min()?)
+    }
+
+    /// Counts a frame shown on the screen, for the statistics.
+    pub fn frame_presented(&self) {
+        self.0.ticker.borrow_mut().frame_presented();
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.0.ticker.borrow().stats()
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.0.clock
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.tasks.borrow().is_empty()
+    }
+}
+
+impl Default for Executor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the time of the running executor's clock.
+///
+/// # Panics
+///
+/// Panics if called outside of [`Executor::run`].
+pub fn now() -> Duration {
+    CURRENT.with_borrow(|x| {
+        x.as_ref()
+            .expect("now() called outside of an executor")
+            .clock
+            .now()
+    })
+}
+
+/// Returns the statistics of the running executor.
+///
+/// # Panics
+///
+/// Panics if called outside of [`Executor::run`].
+pub fn stats() -> Stats {
+    CURRENT.with_borrow(|x| {
+        x.as_ref()
+            .expect("stats() called outside of an executor")
+            .ticker
+            .borrow()
+            .stats()
+    })
+}
+
+pub(super) fn current_ticker() -> Rc<RefCell<Ticker>> {
+    CURRENT.with_borrow(|x| {
+        let executor = x.as_ref().expect("tick polled outside of an executor");
+
+        Rc::clone(&executor.ticker)
+    })
+}
+
+pub(super) fn current_timers() -> Rc<RefCell<TimerWheel>> {
+    CURRENT.with_borrow(|x| {
+        let executor = x.as_ref().expect("timer polled outside of an executor");
+
+        Rc::clone(&executor.timers)
+    })
+}
+
+/// Spawns a task onto the executor which is currently running.
+///
+/// # Panics
+///
+/// Panics if called outside of [`Executor::run`].
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    CURRENT.with_borrow(|x| {
+        x.as_ref()
+            .expect("spawn() called outside of an executor")
+            .spawn(future)
+    })
+}
+
+struct JoinState<T> {
+    output: Option<T>,
+    waker: Option<Waker>,
+    finished: bool,
+    aborted: bool,
+}
+
+/// An owned permission to await a spawned task. Dropping the handle detaches the task.
+pub struct JoinHandle<T> {
+    id: usize,
+    state: Rc<RefCell<JoinState<T>>>,
+    executor: Weak<Inner>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Drops the task without polling it any further. Awaiting the handle afterwards resolves to
+    /// `None` unless the task has already finished.
+    pub fn abort(&self) {
+        if let Some(executor) = self.executor.upgrade() {
+            executor.abort(self.id);
+        }
+
+        let mut state = self.state.borrow_mut();
+        state.aborted = true;
+
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        let state = self.state.borrow();
+
+        state.finished || state.aborted
+    }
+
+    /// Takes the output of the task if it has finished.
+    pub fn try_join(&mut self) -> Option<T> {
+        self.state.borrow_mut().output.take()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.borrow_mut();
+
+        if let Some(output) = state.output.take() {
+            Poll::Ready(Some(output))
+        } else if state.aborted {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+enum MaybeDone<F: Future> {
+    Pending(Pin<Box<F>>),
+    Done(F::Output),
+    Taken,
+}
+
+impl<F: Future> MaybeDone<F> {
+    fn poll(&mut self, cx: &mut Context<'_>) -> bool {
+        if let Self::Pending(future) = self {
+            match future.as_mut().poll(cx) {
+                Poll::Ready(output) => *self = Self::Done(output),
+                Poll::Pending => return false,
+            }
+        }
+
+        true
+    }
+
+    fn take(&mut self) -> F::Output {
+        match std::mem::replace(self, Self::Taken) {
+            Self::Done(output) => output,
+            _ => unreachable!("MaybeDone::take() called before the future is done"),
+        }
+    }
+}
+
+/// Polls both futures concurrently and resolves when both of them are done.
+pub fn join<A: Future, B: Future>(a: A, b: B) -> Join<A, B> {
+    Join {
+        a: MaybeDone::Pending(Box::pin(a)),
+        b: MaybeDone::Pending(Box::pin(b)),
+    }
+}
+
+pub struct Join<A: Future, B: Future> {
+    a: MaybeDone<A>,
+    b: MaybeDone<B>,
+}
+
+// The futures are pinned in their own boxes and the outputs are never pinned.
+impl<A: Future, B: Future> Unpin for Join<A, B> {}
+
+impl<A: Future, B: Future> Future for Join<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let a = self.a.poll(cx);
+        let b = self.b.poll(cx);
+
+        if a && b {
+            Poll::Ready((self.a.take(), self.b.take()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
@@ -1,18 +1,34 @@
 use std::time::Duration;
 
+mod cancel;
+mod clock;
+mod executor;
+mod join;
 mod oneshot;
-mod signal;
+mod select;
+mod ticker;
 mod timer;
 
 use oneshot::Oneshot;
-use timer::Timer;
+use ticker::{NextTick, Ticker};
+use timer::{Timer, TimerWheel};
 
-pub use signal::Signal;
+pub use cancel::{CancelReason, CancellationToken, Cancelled, WaitForCancellation, WithCancel};
+pub use clock::{Clock, ClockMode};
+pub use executor::{now, spawn, stats, Executor, JoinHandle};
+pub use join::{join, Join};
+pub use select::{select, Either, Select};
+pub use ticker::{Stats, TICK, TICK_RATE};
 
 pub fn yield_now() -> impl std::future::Future<Output = ()> {
     Oneshot::default()
 }
 
-pub fn sleep(ms: u64) -> Timer<'static> {
+pub fn sleep(ms: u64) -> Timer {
     Timer::new(Duration::from_millis(ms))
 }
+
+/// Waits for the next fixed update, see [`TICK_RATE`].
+pub fn tick() -> NextTick {
+    NextTick::new()
+}
@@ -0,0 +1,152 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use super::executor::current_ticker;
+
+/// The rate of the fixed updates, the vertical refresh rate of the original VGA mode.
+pub const TICK_RATE: u32 = 70;
+pub const TICK: Duration = Duration::from_nanos(1_000_000_000 / TICK_RATE as u64);
+
+/// Limits the number of ticks issued by a single run, so a long stall doesn't turn into a burst
+/// of updates which takes even longer to catch up with.
+const MAX_TICKS_PER_RUN: u32 = 5;
+
+const STATS_PERIOD: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Stats {
+    /// Fixed updates issued since the start.
+    pub ticks: u64,
+    /// Fixed updates skipped because the game couldn't keep up.
+    pub dropped_ticks: u64,
+    /// How far the clock is between the last tick and the next one, in `0.0..1.0`. Renderers use
+    /// it to interpolate between the last two simulated states.
+    pub alpha: f64,
+    /// Presented frames per second.
+    pub fps: f64,
+    /// Fixed updates per second.
+    pub tps: f64,
+    /// Share of the wall clock time spent running tasks.
+    pub load: f64,
+}
+
+/// Splits the clock time into fixed ticks and keeps the statistics of the game loop.
+#[derive(Default)]
+pub struct Ticker {
+    last: Duration,
+    accumulator: Duration,
+    waiters: Vec<Waker>,
+    stats: Stats,
+    period_start: Duration,
+    period_frames: u32,
+    period_ticks: u32,
+    period_work: Duration,
+}
+
+impl Ticker {
+    /// Returns the number of ticks which are due at `now`.
+    pub fn advance(&mut self, now: Duration) -> u32 {
+        self.accumulator += now.saturating_sub(self.last);
+        self.last = now;
+
+        let due = (self.accumulator.as_nanos() / TICK.as_nanos()) as u32;
+        let ticks = due.min(MAX_TICKS_PER_RUN);
+
+        if due > ticks {
+            self.stats.dropped_ticks += u64::from(due - ticks);
+            self.accumulator -= TICK * (due - ticks);
+        }
+
+        self.accumulator -= TICK * ticks;
+        self.stats.alpha = self.accumulator.as_secs_f64() / TICK.as_secs_f64();
+
+        ticks
+    }
+
+    /// Issues one tick, waking every task which waits for it.
+    pub fn fire(&mut self) {
+        self.stats.ticks += 1;
+        self.period_ticks += 1;
+
+        for waker in self.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+
+    pub fn is_waited(&self) -> bool {
+        !self.waiters.is_empty()
+    }
+
+    /// The clock time of the next tick.
+    pub fn next_tick(&self) -> Duration {
+        self.last + TICK - self.accumulator
+    }
+
+    pub fn frame_presented(&mut self) {
+        self.period_frames += 1;
+    }
+
+    /// Accounts the wall clock time of a run and refreshes the rates once per period.
+    pub fn update_stats(&mut self, now: Duration, work: Duration) {
+        self.period_work += work;
+
+        let period = now.saturating_sub(self.period_start);
+
+        if period >= STATS_PERIOD {
+            let secs = period.as_secs_f64();
+
+            self.stats.fps = f64::from(self.period_frames) / secs;
+            self.stats.tps = f64::from(self.period_ticks) / secs;
+            self.stats.load = self.period_work.as_secs_f64() / secs;
+
+            self.period_start = now;
+            self.period_frames = 0;
+            self.period_ticks = 0;
+            self.period_work = Duration::ZERO;
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+}
+
+/// Resolves at the next fixed update, returning the number of the tick.
+pub struct NextTick {
+    target: Option<u64>,
+}
+
+impl NextTick {
+    pub fn new() -> Self {
+        Self { target: None }
+    }
+}
+
+impl Default for NextTick {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Future for NextTick {
+    type Output = u64;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let ticker = current_ticker();
+        let mut ticker = ticker.borrow_mut();
+
+        let target = *self.target.get_or_insert(ticker.stats.ticks + 1);
+
+        if ticker.stats.ticks >= target {
+            Poll::Ready(ticker.stats.ticks)
+        } else {
+            ticker.waiters.push(cx.waker().clone());
+
+            Poll::Pending
+        }
+    }
+}
@@ -1,47 +1,105 @@
 use std::{
+    cell::RefCell,
+    collections::BTreeMap,
     future::Future,
     pin::Pin,
-    task::{Context, Poll},
-    time::{Duration, Instant},
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+    time::Duration,
 };
 
-pub struct Timer<'a> {
-    instant: Instant,
+use super::{executor::current_timers, now};
+
+type TimerKey = (Duration, u64);
+
+/// Pending deadlines of the sleeping timers, ordered by time. The sequence number keeps the keys of
+/// timers with equal deadlines unique.
+#[derive(Default)]
+pub struct TimerWheel {
+    timers: BTreeMap<TimerKey, Waker>,
+    seq: u64,
+}
+
+impl TimerWheel {
+    fn insert(&mut self, deadline: Duration, waker: Waker) -> TimerKey {
+        let key = (deadline, self.seq);
+        self.seq += 1;
+
+        self.timers.insert(key, waker);
+
+        key
+    }
+
+    /// Wakes (and forgets) every timer whose deadline is not later than `now`.
+    pub fn expire(&mut self, now: Duration) {
+        while let Some(entry) = self.timers.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+
+            entry.remove().wake();
+        }
+    }
+
+    pub fn next_deadline(&self) -> Option<Duration> {
+        self.timers
+            .first_key_value()
+            .map(|((deadline, _), _)| *deadline)
+    }
+}
+
+/// Resolves after the duration has passed on the executor's clock, counting from the first poll.
+pub struct Timer {
+    start: Option<Duration>,
     duration: Duration,
-    cancel: Option<&'a dyn Fn() -> bool>,
+    key: Option<(Weak<RefCell<TimerWheel>>, TimerKey)>,
 }
 
-impl<'a> Timer<'a> {
+impl Timer {
     pub fn new(duration: Duration) -> Self {
         Self {
-            instant: Instant::now(),
+            start: None,
             duration,
-            cancel: None,
+            key: None,
         }
     }
 
-    pub fn with_cancel(mut self, cancel: &'a dyn Fn() -> bool) -> Self {
-        self.cancel = Some(cancel);
-        self
+    fn unregister(&mut self) {
+        if let Some((timers, key)) = self.key.take() {
+            if let Some(timers) = timers.upgrade() {
+                timers.borrow_mut().timers.remove(&key);
+            }
+        }
     }
 }
 
-impl Future for Timer<'_> {
-    type Output = bool;
+impl Future for Timer {
+    type Output = ();
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let time_spent = self.instant.elapsed();
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let now = now();
+        let start = *self.start.get_or_insert(now);
 
-        if time_spent >= self.duration {
-            Poll::Ready(false)
-        } else {
-            if time_spent.as_millis() > 0 && self.cancel.as_ref().map_or(false, |f| f()) {
-                return Poll::Ready(true);
-            }
-
-            cx.waker().wake_by_ref();
+        if now - start >= self.duration {
+            self.unregister();
 
-            Poll::Pending
+            return Poll::Ready(());
         }
+
+        let deadline = start + self.duration;
+
+        let timers = current_timers();
+        let key = timers.borrow_mut().insert(deadline, cx.waker().clone());
+
+        self.unregister();
+        self.key = Some((Rc::downgrade(&timers), key));
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        self.unregister();
     }
 }
@@ -0,0 +1,155 @@
+use lotus3::{
+    game::{
+        options::{Model, Transmission},
+        race::{
+            ai::{Field, Opponent, Skill},
+            physics::{Car, CAR_LENGTH},
+            road::Road,
+        },
+        recs::{Track, MAX_VALUE},
+    },
+    rng::Rng,
+};
+
+fn road(track: &Track) -> Road {
+    Road::new(&track.segments())
+}
+
+fn run(field: &mut Field, road: &Road, player: &mut Car, ticks: u32) {
+    for _ in 0..ticks {
+        field.update(road, std::slice::from_mut(player));
+    }
+}
+
+#[test]
+fn fields_are_the_same_under_a_seed() {
+    let track = Track::parse("XKXCJGFJH-33").unwrap();
+    let road = road(&track);
+
+    let mut a = Field::new(&track, 9, 42);
+    let mut b = Field::new(&track, 9, 42);
+    let mut player_a = Car::new(Model::Esprit, Transmission::Automatic);
+    let mut player_b = player_a.clone();
+
+    run(&mut a, &road, &mut player_a, 70 * 20);
+    run(&mut b, &road, &mut player_b, 70 * 20);
+
+    assert_eq!(a.opponents, b.opponents);
+    assert_eq!(player_a, player_b);
+
+    // and they race: every car has moved on and stays near the road
+    assert!(a.opponents.iter().all(|x| x.car.z > 70.0 * 20.0 * 10.0));
+    assert!(a.opponents.iter().all(|x| x.car.x.abs() < 1.5));
+    assert_eq!(a.position(&player_a), 10);
+}
+
+#[test]
+fn difficulty_makes_the_opponents_faster() {
+    let mut rng = Rng::new(1);
+
+    let easy = Skill::new(0, 0, &mut rng);
+    let hard = Skill::new(MAX_VALUE, 0, &mut rng);
+
+    assert!(hard.pace > easy.pace);
+
+    let distance = |difficulty| {
+        let track = Track {
+            difficulty,
+            curves: 13,
+            ..Track::default()
+        };
+
+        let road = road(&track);
+        let mut field = Field::new(&track, 5, 7);
+
+        // the player keeps up, so the opponents don't wait for it
+        let mut player = Car::new(Model::Esprit, Transmission::Automatic);
+
+        for _ in 0..70 * 30 {
+            player.z = field.opponents.iter().map(|x| x.car.z).sum::<f32>() / 5.0;
+            field.update(&road, std::slice::from_mut(&mut player));
+        }
+
+        field.opponents.iter().map(|x| x.car.z).sum::<f32>()
+    };
+
+    assert!(distance(MAX_VALUE) > distance(0));
+}
+
+#[test]
+fn opponents_wait_for_the_player_and_catch_up() {
+    let track = Track::default();
+    let road = road(&track);
+    let skill = Skill {
+        pace: 0.8,
+        line: 1.0,
+        aggression: 0.0,
+    };
+
+    let mut player = Car::new(Model::Esprit, Transmission::Automatic);
+    player.z = 10_000.0;
+
+    let mut ahead = Opponent::new(Car::new(Model::Esprit, Transmission::Automatic), skill);
+    let mut behind = ahead.clone();
+
+    ahead.car.z = 20_000.0;
+    ahead.car.speed = 70.0;
+    behind.car.speed = 70.0;
+
+    // same speed, the one far behind keeps the throttle down, the one far ahead lifts
+    assert_eq!(behind.drive(&road, None, &player).throttle, 1.0);
+    assert_eq!(ahead.drive(&road, None, &player).throttle, 0.0);
+}
+
+#[test]
+fn opponents_pass_slower_cars_and_bump_the_player() {
+    let track = Track::default();
+    let road = road(&track);
+    let skill = Skill {
+        pace: 0.9,
+        line: 1.0,
+        aggression: 0.0,
+    };
+
+    let mut opponent = Opponent::new(Car::new(Model::M200, Transmission::Automatic), skill);
+    opponent.car.speed = 50.0;
+
+    let mut slow = Car::new(Model::Elan, Transmission::Automatic);
+    slow.z = 2.0 * CAR_LENGTH;
+    slow.speed = 10.0;
+
+    let input = opponent.drive(&road, Some(&slow), &slow);
+    assert!(input.steer != 0.0, "pulls out to pass");
+
+    let mut field = Field::new(&track, 1, 0);
+    let mut player = Car::new(Model::Elan, Transmission::Automatic);
+
+    field.opponents[0].car.z = player.z + CAR_LENGTH / 2.0;
+    field.opponents[0].car.x = player.x;
+    player.speed = 60.0;
+
+    field.update(&road, std::slice::from_mut(&mut player));
+
+    assert!(player.speed < 60.0);
+    assert!(field.opponents[0].car.x != player.x);
+}
+
+#[test]
+fn opponents_race_both_players() {
+    let track = Track::default();
+    let road = road(&track);
+    let mut field = Field::new(&track, 4, 3);
+    let mut players = [
+        Car::new(Model::Esprit, Transmission::Automatic),
+        Car::new(Model::Elan, Transmission::Automatic),
+    ];
+
+    players[1].z = field.opponents[0].car.z + CAR_LENGTH * 4.0;
+
+    for _ in 0..70 * 5 {
+        field.update(&road, &mut players);
+    }
+
+    // the parked car ahead is overtaken by the whole field
+    assert_eq!(field.position(&players[1]), 5);
+}
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use lotus3::{cli::Options, game::Screen};
+
+fn parse(args: &[&str]) -> anyhow::Result<Options> {
+    Options::parse(args.iter().map(|x| x.to_string()))
+}
+
+#[test]
+fn no_arguments_give_the_defaults() {
+    assert_eq!(parse(&[]).unwrap(), Options::default());
+}
+
+#[test]
+fn options_take_values_both_ways() {
+    let options = parse(&[
+        "--data",
+        "other.dat",
+        "--screen=select-model",
+        "--scale",
+        "3",
+        "--set",
+        "course=t2",
+        "--set=p1_name=BOB",
+        "--skip-intro",
+        "--headless",
+        "--replay=run.rec",
+    ])
+    .unwrap();
+
+    assert_eq!(options.data, PathBuf::from("other.dat"));
+    assert_eq!(options.launch.screen, Screen::SelectModel);
+    assert!(options.launch.skip_intro);
+    assert!(!options.launch.skip_protection);
+    assert_eq!(options.scale, Some(3));
+    assert_eq!(
+        options.overrides,
+        [
+            ("course".to_string(), "t2".to_string()),
+            ("p1_name".to_string(), "BOB".to_string())
+        ]
+    );
+    assert!(options.headless);
+    assert_eq!(options.replay, Some(PathBuf::from("run.rec")));
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    for args in [
+        &["--scale", "5"][..],
+        &["--scale", "x"],
+        &["--screen", "garage"],
+        &["--set", "course"],
+        &["--data"],
+        &["--fullscreen=yes"],
+        &["--turbo"],
+        &["--record", "a.rec", "--replay", "b.rec"],
+    ] {
+        assert!(parse(args).is_err(), "{args:?}");
+    }
+}
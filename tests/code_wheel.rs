@@ -0,0 +1,88 @@
+use lotus3::{
+    game::code_wheel::{Challenge, CodeWheel},
+    rng::Rng,
+};
+
+/// Made-up windows of the wheel.
+const WINDOWS: [u32; 12] = [3, 7, 11, 14, 18, 22, 25, 29, 33, 36, 40, 44];
+
+/// The number of helmets around each ring of the made-up wheel.
+const HELMETS: usize = 24;
+
+/// A made-up table in the format of the transcribed wheel, the codes spell the window and the
+/// turn.
+fn table() -> String {
+    WINDOWS
+        .iter()
+        .map(|window| {
+            let codes =
+                (0..HELMETS).map(|turn| format!("{:02}{}", window, (b'a' + turn as u8) as char));
+
+            format!("{window} {}\n", codes.collect::<Vec<_>>().join(" "))
+        })
+        .collect()
+}
+
+#[test]
+fn challenges_come_from_the_wheel() {
+    let wheel = CodeWheel::parse(&table()).unwrap();
+    let mut rng = Rng::new(7);
+
+    assert_eq!(wheel.windows(), WINDOWS);
+    assert_eq!(wheel.helmets(), HELMETS);
+
+    for _ in 0..1000 {
+        let challenge = wheel.challenge(&mut rng);
+
+        assert!(WINDOWS.contains(&challenge.window));
+        assert!(challenge.top < HELMETS && challenge.bottom < HELMETS);
+        assert_ne!(challenge.top, challenge.bottom);
+        assert!((1..HELMETS).contains(&challenge.turn(HELMETS)));
+    }
+}
+
+#[test]
+fn codes_are_read_off_the_transcribed_wheel() {
+    let wheel =
+        CodeWheel::parse(&format!("# window, then the turns 0 to 23\n\n{}", table())).unwrap();
+
+    let challenge = Challenge {
+        window: WINDOWS[0],
+        top: 0,
+        bottom: 1,
+    };
+
+    assert_eq!(wheel.code(&challenge), "03B");
+
+    let turned = Challenge {
+        window: 44,
+        top: 20,
+        bottom: 2,
+    };
+
+    assert_eq!(turned.turn(HELMETS), 6);
+    assert_eq!(wheel.code(&turned), "44G");
+}
+
+#[test]
+fn broken_wheels_are_rejected() {
+    let table = table();
+    let lines = table.lines().collect::<Vec<_>>();
+
+    let repeated = [lines[0], lines[0]].join("\n");
+    let short = table.replace(" 03x", "");
+    let unnumbered = table.replace("3 03a", "A 03a");
+    let long_code = table.replace("03a", "03aa");
+    let single = "3 03a".to_string();
+
+    for text in [
+        String::new(),
+        repeated,
+        short,
+        unnumbered,
+        long_code,
+        single,
+    ] {
+        assert!(CodeWheel::parse(&text).is_err(), "{text}");
+    }
+}
@@ -0,0 +1,116 @@
+use lotus3::{
+    game::{
+        options::Config,
+        race::{
+            environment::{draw_night, Effects, Fog, Particles},
+            road::{
+                race_palette, Camera, Road, RoadColors, Viewport, DRAW_DISTANCE, SEGMENT_LENGTH,
+            },
+        },
+        recs::{Scenery, Segment},
+    },
+    graphics::{palette_color, Point, SCREEN_HEIGHT, SCREEN_WIDTH},
+};
+
+const WHITE: u32 = 0xffff_ffff;
+
+fn buffer() -> Vec<u32> {
+    vec![WHITE; (SCREEN_WIDTH * SCREEN_HEIGHT) as usize]
+}
+
+#[test]
+fn effects_come_with_the_scenery_or_the_option() {
+    assert!(Effects::of(Scenery::Fog).fog);
+    assert!(Effects::of(Scenery::Snow).snow);
+    assert!(Effects::of(Scenery::Night).night);
+    assert_eq!(Effects::of(Scenery::Desert), Effects::default());
+
+    let effects = Effects::from_name("rain,night").unwrap();
+
+    assert!(effects.rain && effects.night && !effects.fog);
+    assert_eq!(Effects::from_name(&effects.name()), Some(effects));
+    assert_eq!(Effects::from_name("none"), Some(Effects::default()));
+    assert_eq!(Effects::from_name("hail"), None);
+    assert!(Effects::of(Scenery::Snow).traction() < Effects::of(Scenery::Storm).traction());
+
+    let mut cfg = Config::new();
+
+    cfg.set("effects", "fog").unwrap();
+    assert_eq!(
+        Config::parse(&cfg.to_string()).unwrap().effects,
+        cfg.effects
+    );
+
+    cfg.set("effects", "scenery").unwrap();
+    assert_eq!(cfg.effects, None);
+    assert!(cfg.set("effects", "fog,hail").is_err());
+}
+
+#[test]
+fn fog_fades_the_distance() {
+    let pal = race_palette(&[], &RoadColors::default());
+    let fog = Fog::new(&pal);
+    let colors = RoadColors::default();
+
+    assert_eq!(fog.palette(0.0), pal.as_slice());
+    assert_eq!(
+        fog.palette(DRAW_DISTANCE as f32 * SEGMENT_LENGTH),
+        fog.sky()
+    );
+
+    let road = Road::new(&[Segment::default(); 500]).with_fog(Fog::new(&pal));
+    let mut buffer = buffer();
+
+    road.draw(
+        &mut buffer,
+        &pal,
+        &Camera::default(),
+        &Viewport::FULL,
+        &colors,
+    );
+
+    // the sky is all fog, the road in front of the car is clear
+    let sky = palette_color(fog.sky(), colors.sky);
+    let near = Point::xy(SCREEN_WIDTH / 2, SCREEN_HEIGHT - 1).index();
+
+    assert_eq!(buffer[0], sky);
+    assert_ne!(sky, palette_color(&pal, colors.sky));
+    assert!(colors
+        .road
+        .iter()
+        .any(|x| buffer[near] == palette_color(&pal, *x)));
+}
+
+#[test]
+fn particles_fall_within_their_view() {
+    let views = Viewport::split(2);
+
+    for mut particles in [Particles::rain(1), Particles::snow(1)] {
+        let mut buffer = buffer();
+
+        for _ in 0..100 {
+            particles.update();
+        }
+
+        particles.draw(&mut buffer, &views[1]);
+
+        let split = Point::xy(0, views[1].y).index();
+
+        assert!(buffer[..split].iter().all(|x| *x == WHITE));
+        assert!(buffer[split..].iter().any(|x| *x != WHITE));
+    }
+}
+
+#[test]
+fn night_leaves_the_headlights_lit() {
+    let mut buffer = buffer();
+
+    draw_night(&mut buffer, &Viewport::FULL);
+
+    let corner = buffer[Point::xy(0, SCREEN_HEIGHT - 1).index()];
+    let beam = buffer[Point::xy(SCREEN_WIDTH / 2, SCREEN_HEIGHT - 1).index()];
+    let sky = buffer[Point::xy(SCREEN_WIDTH / 2, 0).index()];
+
+    assert_eq!(corner, sky);
+    assert!(beam.to_be_bytes()[1] > corner.to_be_bytes()[1]);
+}
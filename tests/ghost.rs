@@ -0,0 +1,61 @@
+use lotus3::game::{
+    race::ghost::{ghost_path, LapRecorder, Trace, SAMPLE_TICKS},
+    recs::Track,
+};
+use std::path::Path;
+
+/// Records a lap at the steady speed, drifting to the right.
+fn lap(ticks: u32, speed: f32) -> Trace {
+    let mut recorder = LapRecorder::default();
+
+    for t in 0..ticks {
+        recorder.record(t, t as f32 * speed, t as f32 / ticks as f32);
+    }
+
+    recorder.finish(ticks)
+}
+
+#[test]
+fn traces_replay_the_lap() {
+    let trace = lap(700, 50.0);
+
+    assert_eq!(trace.lap_ticks(), 700);
+    assert_eq!(trace.position(0), Some((0.0, 0.0)));
+
+    // between the samples the car is interpolated
+    let (z, x) = trace
+        .position(SAMPLE_TICKS * 10 + SAMPLE_TICKS / 2)
+        .unwrap();
+
+    assert!((z - 50.0 * (SAMPLE_TICKS * 10 + SAMPLE_TICKS / 2) as f32).abs() < 1.0);
+    assert!(x > 0.0 && x < 0.1);
+    assert_eq!(trace.position(700), None);
+}
+
+#[test]
+fn traces_survive_a_round_trip() {
+    let trace = lap(500, 80.0);
+    let bytes = trace.to_bytes();
+
+    // 6 bytes a sample
+    assert!(bytes.len() <= 12 + 6 * (500 / SAMPLE_TICKS as usize + 1));
+    assert_eq!(Trace::from_bytes(&bytes).unwrap(), trace);
+    assert!(Trace::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Trace::from_bytes(b"nonsense").is_err());
+}
+
+#[test]
+fn ghosts_are_kept_by_the_track() {
+    let dir = Path::new("ghosts");
+    let a = ghost_path(dir, &Track::default());
+    let b = ghost_path(
+        dir,
+        &Track {
+            curves: 1,
+            ..Track::default()
+        },
+    );
+
+    assert_ne!(a, b);
+    assert!(!a.to_string_lossy().contains(' '));
+}
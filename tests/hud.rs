@@ -0,0 +1,38 @@
+use lotus3::game::{
+    options::{Model, Race, Transmission},
+    race::{
+        hud::{lap_time, Gauges},
+        physics::Car,
+        rules::RaceRules,
+    },
+    recs::Track,
+};
+
+#[test]
+fn gauges_follow_the_race() {
+    let car = Car::new(Model::Esprit, Transmission::Manual);
+    let rules = RaceRules::new(Race::TimeLimit, &Track::default(), 100_000.0);
+    let gauges = Gauges::of(&car, &rules, None);
+
+    assert_eq!(gauges.kmh, 0);
+    assert_eq!(gauges.revs, 0.0);
+    assert_eq!(gauges.gear, 1);
+    assert_eq!(gauges.lap, 1);
+    assert!(gauges.seconds_left.unwrap() > 0);
+    assert_eq!(gauges.fuel, 1.0);
+    assert_eq!(gauges.boost, Some(0.0));
+
+    let car = Car::new(Model::M200, Transmission::Manual);
+    let rules = RaceRules::new(Race::Competition, &Track::default(), 100_000.0);
+    let gauges = Gauges::of(&car, &rules, Some((3, 10)));
+
+    assert_eq!(gauges.seconds_left, None);
+    assert_eq!(gauges.position, Some((3, 10)));
+    assert_eq!(gauges.boost, None);
+}
+
+#[test]
+fn lap_times_have_no_dashes() {
+    assert_eq!(lap_time(0), "0 00 00");
+    assert_eq!(lap_time(70 * 83 + 35), "1 23 50");
+}
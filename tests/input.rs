@@ -0,0 +1,304 @@
+use anyhow::Result;
+use std::{cell::Cell, env, fs, task::Poll, time::Duration};
+
+use lotus3::{
+    data::Archive,
+    engine::{GameEngine, State},
+    game::options::{Acceleration, Config},
+    input::{
+        key_name, parse_key, Action, AnalogAxis, Axis, Combined, Controls, DriverInput,
+        InputDevice, InputEvent, InputHelper, Keyboard, MenuInput, RepeatRate, VirtualDevice,
+    },
+    task::{tick, Clock, TICK},
+};
+use std::{cell::RefCell, rc::Rc};
+use winit::{
+    event::ElementState,
+    keyboard::{Key, NamedKey},
+};
+
+fn event(key: NamedKey, state: ElementState, repeat: bool) -> InputEvent {
+    InputEvent {
+        key: Key::Named(key),
+        state,
+        repeat,
+    }
+}
+
+#[test]
+fn held_keys_survive_clear() {
+    let mut input = InputHelper::new();
+
+    input.handle_event(event(NamedKey::ArrowUp, ElementState::Pressed, false));
+    assert!(input.just_pressed(NamedKey::ArrowUp));
+    assert!(input.is_down(NamedKey::ArrowUp));
+
+    input.clear();
+    input.handle_event(event(NamedKey::ArrowUp, ElementState::Pressed, true));
+    assert!(!input.just_pressed(NamedKey::ArrowUp));
+    assert!(input.key_pressed(NamedKey::ArrowUp));
+    assert!(input.is_down(NamedKey::ArrowUp));
+
+    input.clear();
+    input.handle_event(event(NamedKey::ArrowUp, ElementState::Released, false));
+    assert!(input.just_released(NamedKey::ArrowUp));
+    assert!(!input.is_down(NamedKey::ArrowUp));
+
+    input.clear();
+    assert!(!input.has_events());
+}
+
+#[test]
+fn held_keys_repeat_at_the_given_rate() {
+    let mut input = InputHelper::new().with_repeat_rate(RepeatRate {
+        delay: Duration::from_millis(200),
+        interval: Duration::from_millis(50),
+    });
+
+    input.handle_event(event(NamedKey::ArrowDown, ElementState::Pressed, false));
+    assert!(input.repeating(NamedKey::ArrowDown));
+    assert_eq!(input.next_repeat(), Some(Duration::from_millis(200)));
+
+    let mut repeats = Vec::new();
+
+    for ms in (10..=300).step_by(10) {
+        input.clear();
+        input.update(Duration::from_millis(ms));
+
+        if input.repeating(NamedKey::ArrowDown) {
+            repeats.push(ms);
+        }
+    }
+
+    assert_eq!(repeats, [200, 250, 300]);
+    assert_eq!(input.next_repeat(), Some(Duration::from_millis(350)));
+
+    input.release_all();
+    assert!(input.just_released(NamedKey::ArrowDown));
+    assert_eq!(input.next_repeat(), None);
+}
+
+#[test]
+fn bindings_never_share_a_key() {
+    let mut controls = Controls::default();
+    let p1 = &mut controls.players[0];
+
+    p1.set(Action::Brake, Key::Named(NamedKey::ArrowUp));
+    assert_eq!(p1.get(Action::Brake), &Key::Named(NamedKey::ArrowUp));
+    assert_eq!(p1.get(Action::Accelerate), &Key::Named(NamedKey::ArrowDown));
+
+    let mut input = InputHelper::new();
+    input.handle_event(event(NamedKey::ArrowUp, ElementState::Pressed, false));
+    assert!(controls.player(0).just_pressed(&input, Action::Brake));
+    assert!(!controls.player(1).is_down(&input, Action::Brake));
+    assert!(controls.player(0).pressed(&input, Action::Brake));
+
+    // the key of the other player is swapped too
+    let p2_brake = controls.player(1).get(Action::Brake).clone();
+
+    controls.set(0, Action::GearUp, p2_brake.clone());
+    assert_eq!(controls.player(0).get(Action::GearUp), &p2_brake);
+    assert_eq!(
+        controls.player(1).get(Action::Brake),
+        Controls::default().player(0).get(Action::GearUp)
+    );
+}
+
+#[test]
+fn menus_read_player_1_only() {
+    let controls = Controls::default();
+    let pad = Rc::new(VirtualDevice::new());
+    let menu = MenuInput::new(controls.player(0).clone(), Some(pad.clone()));
+    let mut input = InputHelper::new();
+
+    input.handle_event(event(NamedKey::Tab, ElementState::Pressed, false));
+    input.handle_event(event(NamedKey::Space, ElementState::Pressed, false));
+
+    assert!(controls.player(1).pressed(&input, Action::Back));
+    assert!(!menu.pressed(&input, Action::Back));
+    assert!(!menu.pressed(&input, Action::Confirm));
+
+    // the arrows move the cursor as player 1's steering and pedals
+    input.handle_event(event(NamedKey::ArrowUp, ElementState::Pressed, false));
+
+    assert!(menu.pressed(&input, Action::Accelerate));
+
+    pad.press(Action::Confirm);
+    pad.update();
+
+    assert!(menu.pressed(&input, Action::Confirm));
+}
+
+#[test]
+fn keyboard_drives_along_with_a_device() {
+    let input = Rc::new(RefCell::new(InputHelper::new()));
+    let keyboard = Keyboard::new(Rc::clone(&input), Controls::default().players[0].clone());
+    let pad = Rc::new(VirtualDevice::new());
+    let both = Combined::new(vec![Rc::new(keyboard), pad.clone()]);
+
+    input
+        .borrow_mut()
+        .handle_event(event(NamedKey::ArrowUp, ElementState::Pressed, false));
+    pad.set_axis(Axis::Steering, Some(-0.5));
+
+    assert!(both.is_down(Action::Accelerate));
+    assert!(both.just_pressed(Action::Accelerate));
+    assert_eq!(both.axis(Axis::Pedal), 1.0);
+    assert_eq!(both.axis(Axis::Steering), -0.5);
+
+    pad.press(Action::GearUp);
+    pad.update();
+
+    assert!(both.just_pressed(Action::GearUp));
+}
+
+#[test]
+fn key_names_round_trip() {
+    for bindings in &Controls::default().players {
+        for action in Action::ALL {
+            let key = bindings.get(action);
+            let name = key_name(key).unwrap();
+
+            assert_eq!(parse_key(&name).as_ref(), Some(key), "{name}");
+        }
+    }
+
+    assert_eq!(key_name(&Key::Named(NamedKey::F1)), None);
+    assert_eq!(parse_key("W"), Some(Key::Character("w".into())));
+}
+
+#[test]
+fn driver_input_follows_the_acceleration_mode() {
+    let pad = VirtualDevice::new();
+
+    pad.press(Action::Accelerate);
+    pad.press(Action::GearUp);
+    pad.set_axis(Axis::Steering, Some(-0.25));
+    pad.set_axis(Axis::Pedal, Some(-0.5));
+    assert!(pad.update());
+
+    let buttons = DriverInput::read(&pad, Acceleration::Button);
+    assert_eq!(buttons.steer, -0.25);
+    assert_eq!((buttons.throttle, buttons.brake), (1.0, 0.0));
+    assert!(buttons.gear_up && !buttons.gear_down);
+
+    let joystick = DriverInput::read(&pad, Acceleration::Joystick);
+    assert_eq!((joystick.throttle, joystick.brake), (0.0, 0.5));
+
+    // the gear change is an edge, holding the button doesn't repeat it
+    assert!(pad.update());
+    assert!(!DriverInput::read(&pad, Acceleration::Button).gear_up);
+    assert!(!pad.update());
+}
+
+#[test]
+fn keyboard_axes_are_digital() {
+    let input = Rc::new(RefCell::new(InputHelper::new()));
+    let keyboard = Keyboard::new(Rc::clone(&input), Controls::default().players[0].clone());
+
+    input
+        .borrow_mut()
+        .handle_event(event(NamedKey::ArrowLeft, ElementState::Pressed, false));
+    assert_eq!(keyboard.axis(Axis::Steering), -1.0);
+    assert_eq!(keyboard.axis(Axis::Pedal), 0.0);
+
+    input
+        .borrow_mut()
+        .handle_event(event(NamedKey::ArrowRight, ElementState::Pressed, false));
+    assert_eq!(keyboard.axis(Axis::Steering), 0.0);
+}
+
+#[test]
+fn analog_axis_has_a_dead_zone() {
+    let axis = AnalogAxis {
+        min: 0,
+        max: 200,
+        dead_zone: 0.2,
+    };
+
+    assert_eq!(axis.normalize(100), 0.0);
+    assert_eq!(axis.normalize(115), 0.0);
+    assert_eq!(axis.normalize(200), 1.0);
+    assert_eq!(axis.normalize(0), -1.0);
+    assert!((axis.normalize(160) - 0.5).abs() < 1e-6);
+    assert_eq!(axis.normalize(-50), -1.0);
+}
+
+thread_local! {
+    static GEAR_UPS: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Counts the gear changes up the first player makes, reading the input once per tick like the
+/// races do.
+async fn shifting(state: State) -> Result<()> {
+    let device = state.device(0);
+
+    loop {
+        tick().await;
+
+        if DriverInput::read(device.as_ref(), Acceleration::Button).gear_up {
+            GEAR_UPS.set(GEAR_UPS.get() + 1);
+        }
+    }
+}
+
+fn shifting_engine(name: &str) -> GameEngine {
+    let path = env::temp_dir().join(format!("lotus3-{}-{name}.dat", std::process::id()));
+    fs::write(&path, [0; 22]).unwrap();
+
+    GEAR_UPS.set(0);
+
+    GameEngine::new(
+        Archive::open(&path).unwrap(),
+        Config::new(),
+        Clock::manual(),
+        shifting,
+    )
+    .unwrap()
+}
+
+fn step(engine: &mut GameEngine) {
+    assert_eq!(engine.step().unwrap(), Poll::Pending);
+}
+
+fn gear_up(state: ElementState) -> InputEvent {
+    InputEvent {
+        key: Controls::default().player(0).get(Action::GearUp).clone(),
+        state,
+        repeat: false,
+    }
+}
+
+#[test]
+fn presses_between_ticks_wait_for_the_next_tick() {
+    let mut engine = shifting_engine("between");
+    step(&mut engine);
+
+    // pressed and released within steps which have no tick
+    engine.handle_event(gear_up(ElementState::Pressed));
+    step(&mut engine);
+    engine.handle_event(gear_up(ElementState::Released));
+    step(&mut engine);
+    assert_eq!(GEAR_UPS.get(), 0);
+
+    engine.clock().advance(TICK);
+    step(&mut engine);
+    assert_eq!(GEAR_UPS.get(), 1);
+
+    engine.clock().advance(TICK);
+    step(&mut engine);
+    assert_eq!(GEAR_UPS.get(), 1);
+}
+
+#[test]
+fn catching_up_ticks_see_a_press_once() {
+    let mut engine = shifting_engine("catch-up");
+    step(&mut engine);
+
+    engine.handle_event(gear_up(ElementState::Pressed));
+    engine.clock().advance(TICK * 3);
+    step(&mut engine);
+
+    assert_eq!(engine.stats().ticks, 3);
+    assert_eq!(GEAR_UPS.get(), 1);
+}
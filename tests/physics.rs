@@ -0,0 +1,243 @@
+use lotus3::{
+    game::{
+        options::{Model, Transmission},
+        race::{
+            physics::{Car, Handling, CAR_WIDTH},
+            road::{Road, SEGMENT_LENGTH},
+            scenery::Placement,
+        },
+        recs::Segment,
+    },
+    input::DriverInput,
+};
+
+fn straight() -> Road {
+    Road::new(&[Segment::default(); 100])
+}
+
+fn full_throttle() -> DriverInput {
+    DriverInput {
+        throttle: 1.0,
+        ..DriverInput::default()
+    }
+}
+
+fn drive(car: &mut Car, road: &Road, input: DriverInput, ticks: u32) {
+    for _ in 0..ticks {
+        car.update(&input, road);
+    }
+}
+
+#[test]
+fn automatic_cars_shift_up_to_near_the_top_speed() {
+    let road = straight();
+
+    for model in [Model::Esprit, Model::Elan, Model::M200] {
+        let mut car = Car::new(model, Transmission::Automatic);
+        let handling = Handling::of(model);
+
+        drive(&mut car, &road, full_throttle(), 70 * 60);
+
+        assert_eq!(car.gear, handling.gears.len() - 1, "{model:?}");
+        assert!(
+            car.speed > handling.top_speed * 0.8,
+            "{model:?} {}",
+            car.speed
+        );
+        assert!(car.speed <= handling.top_speed);
+        assert!(car.rpm <= handling.max_rpm);
+        assert!(car.z > 0.0);
+    }
+
+    let top = |model| {
+        let mut car = Car::new(model, Transmission::Automatic);
+        drive(&mut car, &road, full_throttle(), 70 * 60);
+        car.speed
+    };
+
+    assert!(top(Model::M200) > top(Model::Elan));
+}
+
+#[test]
+fn manual_cars_stay_in_gear() {
+    let road = straight();
+    let mut car = Car::new(Model::Esprit, Transmission::Manual);
+
+    drive(&mut car, &road, full_throttle(), 70 * 10);
+
+    assert_eq!(car.gear, 0);
+    assert!(
+        car.speed <= Handling::of(Model::Esprit).gears[0] * Handling::of(Model::Esprit).top_speed
+    );
+
+    let speed = car.speed;
+
+    car.update(
+        &DriverInput {
+            gear_up: true,
+            ..full_throttle()
+        },
+        &road,
+    );
+    drive(&mut car, &road, full_throttle(), 70 * 5);
+
+    assert_eq!(car.gear, 1);
+    assert!(car.speed > speed);
+}
+
+#[test]
+fn braking_and_the_grass_slow_down() {
+    let road = straight();
+    let mut car = Car::new(Model::Elan, Transmission::Automatic);
+
+    drive(&mut car, &road, full_throttle(), 70 * 30);
+    let speed = car.speed;
+
+    let mut braking = car.clone();
+    drive(
+        &mut braking,
+        &road,
+        DriverInput {
+            brake: 1.0,
+            ..DriverInput::default()
+        },
+        70,
+    );
+    assert_eq!(braking.speed, 0.0);
+
+    car.x = 2.0;
+    drive(&mut car, &road, full_throttle(), 70 * 5);
+    assert!(car.is_off_road());
+    assert!(car.speed < speed / 2.0, "{} {speed}", car.speed);
+}
+
+#[test]
+fn curves_push_the_car_out() {
+    let curve = Segment {
+        curve: 1.0,
+        slope: 0.0,
+    };
+
+    let road = Road::new(&[curve; 100]);
+    let mut car = Car::new(Model::Esprit, Transmission::Automatic);
+
+    drive(&mut car, &road, full_throttle(), 70 * 10);
+
+    // the road bends to the right, the car drifts to the left
+    assert!(car.x < -0.1, "{}", car.x);
+}
+
+#[test]
+fn collisions_slow_the_cars() {
+    let road = straight();
+    let mut car = Car::new(Model::Esprit, Transmission::Automatic);
+
+    drive(&mut car, &road, full_throttle(), 70 * 10);
+    let speed = car.speed;
+
+    let rock = Placement {
+        segment: 0,
+        offset: CAR_WIDTH / 2.0,
+        image: 0,
+        obstacle: true,
+    };
+
+    assert!(car.collide_objects(&[rock]));
+    assert!(car.speed < speed / 2.0);
+
+    // no pull until the car has recovered
+    let crashed = car.speed;
+    car.update(&full_throttle(), &road);
+    assert!(car.speed < crashed);
+
+    let mut ahead = Car::new(Model::Elan, Transmission::Automatic);
+    let mut behind = Car::new(Model::Esprit, Transmission::Automatic);
+
+    ahead.z = 1000.0;
+    ahead.speed = 20.0;
+    behind.z = 950.0;
+    behind.speed = 60.0;
+    behind.x = 0.1;
+
+    assert!(behind.collide(&mut ahead));
+    assert_eq!(behind.speed, 20.0);
+    assert!(ahead.speed > 20.0);
+    assert!(behind.x > ahead.x);
+
+    ahead.z = 5000.0;
+    assert!(!behind.collide(&mut ahead));
+}
+
+#[test]
+fn cars_drive_off_after_crashing_into_an_obstacle() {
+    let road = straight();
+    let mut car = Car::new(Model::Esprit, Transmission::Automatic);
+
+    let rock = Placement {
+        segment: 20,
+        offset: 0.0,
+        image: 0,
+        obstacle: true,
+    };
+
+    let past = (rock.segment + 1) as f32 * SEGMENT_LENGTH;
+    let mut crashes = 0;
+
+    for _ in 0..70 * 10 {
+        car.update(&full_throttle(), &road);
+
+        if road.segment_at(car.z) == rock.segment && car.collide_objects(&[rock]) {
+            crashes += 1;
+        }
+
+        if car.z > past {
+            break;
+        }
+    }
+
+    assert_eq!(crashes, 1);
+    assert!(car.z > past, "{}", car.z);
+    assert_ne!(car.x, rock.offset);
+}
+
+#[test]
+fn turbo_spools_up_and_fuel_runs_down() {
+    let road = straight();
+    let mut esprit = Car::new(Model::Esprit, Transmission::Automatic);
+    let mut m200 = Car::new(Model::M200, Transmission::Automatic);
+
+    drive(&mut esprit, &road, full_throttle(), 70 * 5);
+    drive(&mut m200, &road, full_throttle(), 70 * 5);
+
+    assert!(esprit.boost > 0.0);
+    assert_eq!(m200.boost, 0.0);
+    assert!(esprit.fuel < 1.0);
+
+    // a dry tank gives no pull
+    esprit.fuel = 0.0;
+    let speed = esprit.speed;
+    drive(&mut esprit, &road, full_throttle(), 70);
+
+    assert!(esprit.speed < speed);
+}
+
+#[test]
+fn wet_roads_hold_less_in_the_curves() {
+    let road = Road::new(
+        &[Segment {
+            curve: 1.0,
+            ..Segment::default()
+        }; 100],
+    );
+
+    let drift = |traction| {
+        let mut car = Car::new(Model::Esprit, Transmission::Automatic);
+        car.speed = Handling::of(Model::Esprit).top_speed;
+        car.traction = traction;
+
+        drive(&mut car, &road, DriverInput::default(), 20);
+        car.x
+    };
+
+    assert!(drift(0.5).abs() > drift(1.0).abs());
+}
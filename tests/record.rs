@@ -0,0 +1,213 @@
+use anyhow::Result;
+use std::{cell::RefCell, env, fs, path::PathBuf, task::Poll, time::Duration};
+
+use lotus3::{
+    data::Archive,
+    engine::{GameEngine, State},
+    game::options::{Config, Course},
+    input::{Action, InputEvent, RecordedEvent, Recording},
+    task::{now, tick, Clock},
+};
+use winit::{
+    event::ElementState,
+    keyboard::{Key, NamedKey},
+};
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("lotus3-{}-{name}", std::process::id()))
+}
+
+/// An archive without any items, the test games don't load anything.
+fn empty_archive(name: &str) -> Archive {
+    let path = temp_path(&format!("{name}.dat"));
+    fs::write(&path, [0; 22]).unwrap();
+
+    Archive::open(&path).unwrap()
+}
+
+fn press(key: Key, state: ElementState) -> InputEvent {
+    InputEvent {
+        key,
+        state,
+        repeat: false,
+    }
+}
+
+/// Logs every key with the time it came at and a random number, until Escape.
+async fn game(mut state: State) -> Result<()> {
+    loop {
+        tick().await;
+
+        let input = state.input.borrow();
+
+        for key in input.keys() {
+            let n = state.rng.below(1000);
+
+            LOG.with_borrow_mut(|x| {
+                x.push(format!("{key:?} {:?} {n} {:?}", now(), state.cfg.course))
+            });
+        }
+
+        if state.menu_input().pressed(&input, Action::Back) {
+            break Ok(());
+        }
+    }
+}
+
+fn run(mut engine: GameEngine, events: &[(u64, InputEvent)]) -> Vec<String> {
+    LOG.with_borrow_mut(Vec::clear);
+
+    while engine.steps() < 1000 {
+        let steps = engine.steps();
+
+        for (_, event) in events.iter().filter(|(step, _)| *step == steps) {
+            engine.handle_event(event.clone());
+        }
+
+        if engine.step().unwrap() == Poll::Ready(()) {
+            break;
+        }
+    }
+
+    LOG.with_borrow_mut(std::mem::take)
+}
+
+#[test]
+fn replay_reproduces_the_run() {
+    let path = temp_path("replay.rec");
+
+    let mut cfg = Config::new();
+    cfg.course = Course::Circular;
+
+    let events = [
+        (3, press(Key::Character("a".into()), ElementState::Pressed)),
+        (4, press(Key::Character("a".into()), ElementState::Released)),
+        (
+            10,
+            press(Key::Named(NamedKey::Enter), ElementState::Pressed),
+        ),
+        (10, press(Key::Character("?".into()), ElementState::Pressed)),
+        (
+            25,
+            press(Key::Named(NamedKey::Escape), ElementState::Pressed),
+        ),
+    ];
+
+    let clock = Clock::fixed_step(Duration::from_millis(10));
+    let engine = GameEngine::new(empty_archive("record"), cfg.clone(), clock, game)
+        .unwrap()
+        .with_seed(42)
+        .with_recorder(&path);
+
+    let recorded = run(engine, &events);
+    assert_eq!(recorded.len(), 4);
+    assert!(recorded[0].contains("Circular"));
+
+    let recording = Recording::load(&path).unwrap();
+    assert_eq!(recording.seed, 42);
+    assert_eq!(recording.cfg, cfg);
+    assert_eq!(recording.events.len(), events.len());
+
+    // the keyboard is ignored during the replay
+    let noise = [(
+        12,
+        press(Key::Named(NamedKey::Escape), ElementState::Pressed),
+    )];
+
+    let engine = GameEngine::new(
+        empty_archive("replay"),
+        Config::new(),
+        Clock::real_time(),
+        game,
+    )
+    .unwrap()
+    .with_playback(recording.clone());
+
+    assert_eq!(run(engine, &noise), recorded);
+
+    assert_eq!(Recording::parse(&recording.to_string()).unwrap(), recording);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn recording_needs_a_fixed_step_clock() {
+    let mut engine = GameEngine::new(
+        empty_archive("manual"),
+        Config::new(),
+        Clock::manual(),
+        game,
+    )
+    .unwrap()
+    .with_recorder(temp_path("manual.rec"));
+
+    assert!(engine.step().is_err());
+}
+
+#[test]
+fn config_round_trips_through_text() {
+    let mut cfg = Config::new();
+    cfg.p1_name = "ANN".to_string();
+    cfg.code = "         -38".to_string();
+    cfg.controls.players[1].set(Action::Brake, Key::Character("x".into()));
+
+    assert_eq!(Config::parse(&cfg.to_string()).unwrap(), cfg);
+    assert!(Config::parse("players_num=3").is_err());
+    assert!(Config::parse("colour=red").is_err());
+    assert!(Config::parse("code=XKXCJGFJH-16").is_err());
+    assert!(Config::parse("code").is_err());
+}
+
+/// Plays a demo with the A key held from 20 to 50 ms after its start, logging what the game sees.
+async fn demo_game(state: State) -> Result<()> {
+    let mut demo = Recording::new(1, Duration::from_millis(10), Config::new());
+
+    for (step, state) in [(2, ElementState::Pressed), (5, ElementState::Released)] {
+        demo.events.push(RecordedEvent {
+            step,
+            event: press(Key::Character("a".into()), state),
+        });
+    }
+
+    state.session.borrow_mut().play(&demo, now());
+
+    while state.session.borrow().is_playing() {
+        tick().await;
+
+        let input = state.input.borrow();
+
+        if input.just_pressed(Key::Character("a".into())) {
+            LOG.with_borrow_mut(|x| x.push(format!("down {:?}", now())));
+        }
+
+        if input.just_released(Key::Character("a".into())) {
+            LOG.with_borrow_mut(|x| x.push(format!("up {:?}", now())));
+        }
+    }
+
+    let interrupted = state.session.borrow().interrupted();
+    LOG.with_borrow_mut(|x| x.push(format!("interrupted {interrupted}")));
+
+    Ok(())
+}
+
+#[test]
+fn session_plays_by_time_and_notices_the_keyboard() {
+    let clock = Clock::fixed_step(Duration::from_millis(5));
+    let engine =
+        GameEngine::new(empty_archive("session"), Config::new(), clock, demo_game).unwrap();
+
+    // the live key is swallowed, it only marks the playback as interrupted
+    let live = [(3, press(Key::Character("a".into()), ElementState::Pressed))];
+
+    // the playback starts at 5 ms, so its events are due at 25 and 55 ms and the game sees them
+    // in the first step after that
+    assert_eq!(
+        run(engine, &live),
+        ["down 30ms", "up 60ms", "interrupted true"]
+    );
+}
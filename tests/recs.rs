@@ -0,0 +1,104 @@
+use lotus3::game::{
+    options::Config,
+    recs::{Scenery, Track, MAX_VALUE, MIN_SEGMENTS, SEGMENTS_PER_LENGTH},
+};
+
+#[test]
+fn codes_round_trip() {
+    for code in ["XKXCJGFJH-33", "         -38", &Config::new().code] {
+        assert_eq!(Track::parse(code).unwrap().code(), code);
+    }
+
+    let track = Track::parse("xkxcjgfjh-33").unwrap();
+
+    assert_eq!(track.curves, 24);
+    assert_eq!(track.hills, 11);
+    assert_eq!(track.difficulty, 7);
+    assert_eq!(track.scenery, Scenery::Roadworks);
+
+    let track = Track {
+        curves: MAX_VALUE,
+        steepness: 3,
+        scenery: Scenery::Mountains,
+        ..Track::default()
+    };
+
+    assert_eq!(Track::parse(&track.code()).unwrap(), track);
+}
+
+#[test]
+fn shown_codes_are_accepted() {
+    for code in ["XKXCJGFJH-33", "VBJD D   -99"] {
+        assert!(Track::parse(code).is_ok(), "{code}");
+    }
+
+    assert_eq!(Config::new().code, "VBJD D   -99");
+}
+
+#[test]
+fn changed_and_swapped_letters_are_caught() {
+    let letters = b"XKXCJGFJH";
+
+    for i in 0..letters.len() {
+        for j in i + 1..letters.len() {
+            if letters[i] == letters[j] {
+                continue;
+            }
+
+            let mut swapped = *letters;
+            swapped.swap(i, j);
+
+            let code = format!("{}-33", std::str::from_utf8(&swapped).unwrap());
+            assert!(Track::parse(&code).is_err(), "{code}");
+        }
+
+        let mut changed = *letters;
+        changed[i] = if changed[i] == b'A' { b'B' } else { b'A' };
+
+        let code = format!("{}-33", std::str::from_utf8(&changed).unwrap());
+        assert!(Track::parse(&code).is_err(), "{code}");
+    }
+}
+
+#[test]
+fn invalid_codes_are_rejected() {
+    for code in [
+        "XKXCJGFJH-16", // wrong checksum
+        "XKXCJGFJH16",
+        "XKXCJGFJ-16",
+        "XKXCJGFJ1-16",
+        "XKXCJGFJH-1X",
+        "        Z-92", // no such scenery
+    ] {
+        assert!(Track::parse(code).is_err(), "{code}");
+        assert_eq!(Track::parse_or_default(code), Track::default());
+    }
+}
+
+#[test]
+fn tracks_are_generated_deterministically() {
+    let track = Track::parse("XKXCJGFJH-33").unwrap();
+    let segments = track.segments();
+
+    assert_eq!(segments, track.segments());
+    assert_eq!(
+        segments.len(),
+        MIN_SEGMENTS + track.length as usize * SEGMENTS_PER_LENGTH
+    );
+    assert!(segments.iter().any(|x| x.curve != 0.0));
+    assert!(segments
+        .iter()
+        .all(|x| x.curve.abs() <= 1.0 && x.slope.abs() <= 1.0));
+
+    let straight = Track::default().segments();
+
+    assert!(straight.iter().all(|x| x.curve == 0.0 && x.slope == 0.0));
+    assert_ne!(
+        Track {
+            variation: 1,
+            ..track
+        }
+        .segments(),
+        segments
+    );
+}
@@ -0,0 +1,155 @@
+use lotus3::{
+    game::{
+        race::road::{
+            race_palette, Camera, Road, RoadColors, Viewport, DRAW_DISTANCE, SEGMENT_LENGTH,
+        },
+        recs::Segment,
+    },
+    graphics::{palette_color, Image, Point, Size, SCREEN_HEIGHT, SCREEN_WIDTH},
+};
+
+fn draw(road: &Road, camera: &Camera) -> Vec<u32> {
+    let mut buffer = vec![0; (SCREEN_WIDTH * SCREEN_HEIGHT) as usize];
+
+    road.draw(
+        &mut buffer,
+        &race_palette(&[], &RoadColors::default()),
+        camera,
+        &Viewport::FULL,
+        &RoadColors::default(),
+    );
+
+    buffer
+}
+
+/// Returns the middle of the road on the row.
+fn road_centre(buffer: &[u32], y: u32) -> f32 {
+    let colors = RoadColors::default();
+    let road = colors
+        .road
+        .map(|x| palette_color(&race_palette(&[], &RoadColors::default()), x));
+
+    let xs = (0..SCREEN_WIDTH)
+        .filter(|x| road.contains(&buffer[Point::xy(*x, y).index()]))
+        .collect::<Vec<_>>();
+
+    (xs[0] + xs[xs.len() - 1]) as f32 / 2.0
+}
+
+#[test]
+fn straight_road_is_centred_below_the_sky() {
+    let road = Road::new(&[Segment::default(); 500]);
+    let buffer = draw(&road, &Camera::default());
+
+    let pal = race_palette(&[], &RoadColors::default());
+    let colors = RoadColors::default();
+
+    assert_eq!(buffer[0], palette_color(&pal, colors.sky));
+    assert!(colors
+        .grass
+        .map(|x| palette_color(&pal, x))
+        .contains(&buffer[Point::xy(0, SCREEN_HEIGHT / 2 + 20).index()]));
+
+    let centre = road_centre(&buffer, SCREEN_HEIGHT - 1);
+    assert!(
+        (centre - SCREEN_WIDTH as f32 / 2.0).abs() <= 1.0,
+        "{centre}"
+    );
+}
+
+#[test]
+fn curves_bend_the_road_away() {
+    let curve = Segment {
+        curve: 1.0,
+        slope: 0.0,
+    };
+
+    let road = Road::new(&[curve; 500]);
+    let buffer = draw(&road, &Camera::default());
+
+    let near = road_centre(&buffer, SCREEN_HEIGHT - 1);
+    let far = road_centre(&buffer, SCREEN_HEIGHT / 2 + 5);
+
+    assert!(far > near + 10.0, "{near} {far}");
+}
+
+#[test]
+fn the_road_wraps_around_the_lap() {
+    let hill = Segment {
+        curve: 0.0,
+        slope: 1.0,
+    };
+
+    let road = Road::new(&[hill; 100]);
+
+    assert_eq!(road.length(), 100.0 * SEGMENT_LENGTH);
+    assert_eq!(road.segment_at(road.length() + SEGMENT_LENGTH * 1.5), 1);
+    assert_eq!(road.segment_at(-SEGMENT_LENGTH / 2.0), 99);
+
+    // the end of the lap meets the start
+    let last = road.segments().last().unwrap();
+    assert!((last.y2 - road.segments()[0].y1).abs() < 0.01);
+
+    let camera = Camera {
+        z: road.length() - SEGMENT_LENGTH,
+        ..Camera::default()
+    };
+
+    let mut buffer = vec![0; (SCREEN_WIDTH * SCREEN_HEIGHT) as usize];
+    let projections = road.draw(
+        &mut buffer,
+        &race_palette(&[], &RoadColors::default()),
+        &camera,
+        &Viewport::FULL,
+        &RoadColors::default(),
+    );
+
+    assert_eq!(projections.len(), DRAW_DISTANCE.min(100) - 1);
+    assert_eq!(projections[0].index, 0);
+    assert!(projections.windows(2).all(|x| x[0].scale > x[1].scale));
+}
+
+#[test]
+fn split_screen_views_stay_apart() {
+    let views = Viewport::split(2);
+
+    assert_eq!(Viewport::split(1), vec![Viewport::FULL]);
+    assert_eq!(views.len(), 2);
+    assert_eq!(views[0].y + views[0].height, views[1].y);
+    assert_eq!(views[1].y + views[1].height, SCREEN_HEIGHT);
+
+    // the road of the top view leaves the bottom one untouched
+    let road = Road::new(&[Segment::default(); 500]);
+    let mut buffer = vec![0; (SCREEN_WIDTH * SCREEN_HEIGHT) as usize];
+
+    road.draw(
+        &mut buffer,
+        &race_palette(&[], &RoadColors::default()),
+        &Camera::default(),
+        &views[0],
+        &RoadColors::default(),
+    );
+
+    let split = Point::xy(0, views[1].y).index();
+
+    assert!(buffer[..split].iter().all(|x| *x != 0));
+    assert!(buffer[split..].iter().all(|x| *x == 0));
+}
+
+#[test]
+fn road_takes_the_entries_no_sprite_uses() {
+    let base = (0..=255).flat_map(|x: u8| [x >> 2; 3]).collect::<Vec<_>>();
+    let sprite = Image::new(Size::wh(3, 1), vec![Some(255), None, Some(250)]);
+    let colors = RoadColors::unused_by([&sprite]);
+    let pal = race_palette(&base, &colors);
+
+    assert_eq!(colors.lane, 254);
+    assert_eq!(colors.sky, 246);
+    assert_eq!(palette_color(&pal, 255), palette_color(&base, 255));
+    assert_eq!(palette_color(&pal, 250), palette_color(&base, 250));
+    assert_eq!(palette_color(&pal, 0), palette_color(&base, 0));
+    assert_ne!(
+        palette_color(&pal, colors.sky),
+        palette_color(&base, colors.sky)
+    );
+}
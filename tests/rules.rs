@@ -0,0 +1,143 @@
+use lotus3::game::{
+    options::{Course, Race},
+    race::rules::{
+        course_tracks, format_time, points, CourseResults, Event, RaceRules, Status, CHECKPOINTS,
+        LAPS, QUALIFYING_BONUS, QUALIFYING_POSITION,
+    },
+    recs::Track,
+};
+
+const LAP_LENGTH: f32 = 100_000.0;
+
+/// Drives at the speed until the race is over, returns the events.
+fn drive(rules: &mut RaceRules, speed: f32) -> Vec<Event> {
+    let mut z = 0.0;
+    let mut events = Vec::new();
+
+    while rules.status() == Status::Racing {
+        z += speed;
+        events.extend(rules.update(z));
+    }
+
+    events
+}
+
+#[test]
+fn time_limit_is_extended_at_checkpoints() {
+    let mut rules = RaceRules::new(Race::TimeLimit, &Track::default(), LAP_LENGTH);
+    let events = drive(&mut rules, 100.0);
+
+    let extensions = events
+        .iter()
+        .filter(|x| matches!(x, Event::Checkpoint(_)))
+        .count();
+
+    assert_eq!(extensions, (LAPS * (CHECKPOINTS - 1)) as usize);
+    assert_eq!(events.last(), Some(&Event::Finished));
+    assert_eq!(rules.lap_times().len(), LAPS as usize);
+
+    rules.set_position(1);
+    let result = rules.result();
+
+    assert!(result.qualified);
+    assert_eq!(result.position, None);
+    assert_eq!(result.points, 0);
+}
+
+#[test]
+fn time_limit_runs_out_when_too_slow() {
+    let mut rules = RaceRules::new(Race::TimeLimit, &Track::default(), LAP_LENGTH);
+    let events = drive(&mut rules, 10.0);
+
+    assert_eq!(events.last(), Some(&Event::OutOfTime));
+    assert_eq!(rules.time_left(), Some(0));
+
+    let result = rules.result();
+
+    assert_eq!(result.status, Status::OutOfTime);
+    assert!(!result.qualified);
+}
+
+#[test]
+fn competition_scores_and_qualifies_by_position() {
+    let mut rules = RaceRules::new(Race::Competition, &Track::default(), LAP_LENGTH);
+    let events = drive(&mut rules, 10.0);
+
+    // no clock in a competition, laps are counted all the same
+    assert_eq!(rules.time_left(), None);
+    assert!(events.contains(&Event::Lap(LAPS)));
+    assert_eq!(events.last(), Some(&Event::Finished));
+
+    rules.set_position(1);
+    let winner = rules.result();
+
+    assert_eq!(winner.position, Some(1));
+    assert_eq!(winner.points, points(1));
+    assert!(winner.qualified);
+
+    rules.set_position(QUALIFYING_POSITION + 1);
+
+    assert!(!rules.result().qualified);
+    assert_eq!(points(0), 0);
+    assert_eq!(points(100), 0);
+}
+
+#[test]
+fn course_ends_at_failed_qualification() {
+    let custom = Track::default();
+    let races = course_tracks(Course::T1, &custom).len();
+    let mut rules = RaceRules::new(Race::Competition, &custom, LAP_LENGTH);
+    drive(&mut rules, 1000.0);
+
+    let mut results = CourseResults::default();
+    rules.set_position(1);
+    results.add(rules.result());
+
+    assert!(results.goes_on(races));
+
+    rules.set_position(QUALIFYING_POSITION + 1);
+    results.add(rules.result());
+
+    assert!(!results.goes_on(races));
+    assert!(!results.is_complete(races));
+    assert_eq!(
+        results.points(),
+        points(1) + points(QUALIFYING_POSITION + 1)
+    );
+}
+
+#[test]
+fn courses_have_valid_races() {
+    let custom = Track::parse("XKXCJGFJH-33").unwrap();
+
+    assert_eq!(course_tracks(Course::Unknown, &custom), vec![custom]);
+
+    for course in [Course::T1, Course::T2, Course::T3, Course::Circular] {
+        let tracks = course_tracks(course, &custom);
+
+        assert!(tracks.len() > 1);
+
+        for track in tracks {
+            assert_eq!(Track::parse(&track.code()).ok(), Some(track));
+        }
+    }
+
+    assert_eq!(format_time(70 * 83 + 35), "1-23-50");
+}
+
+#[test]
+fn course_score_counts_points_and_qualifications() {
+    let track = Track::default();
+    let mut results = CourseResults::default();
+
+    let mut rules = RaceRules::new(Race::Competition, &track, LAP_LENGTH);
+    drive(&mut rules, 1000.0);
+    rules.set_position(1);
+    results.add(rules.result());
+
+    let mut rules = RaceRules::new(Race::TimeLimit, &track, LAP_LENGTH);
+    drive(&mut rules, 1000.0);
+    results.add(rules.result());
+
+    assert_eq!(results.score(), points(1) + 2 * QUALIFYING_BONUS);
+}
@@ -0,0 +1,153 @@
+use lotus3::{
+    game::{
+        race::{
+            road::Viewport,
+            scenery::{draw_billboards, place, Billboard},
+        },
+        recs::{Track, MAX_VALUE},
+    },
+    graphics::{blend, palette_color, Clip, Image, Point, Size, SCREEN_HEIGHT, SCREEN_WIDTH},
+};
+
+fn palette() -> Vec<u8> {
+    (0..=255).flat_map(|x: u8| [x >> 2; 3]).collect()
+}
+
+fn pixel(buffer: &[u32], x: u32, y: u32) -> u32 {
+    buffer[Point::xy(x, y).index()]
+}
+
+#[test]
+fn images_are_scaled_and_clipped() {
+    let pal = palette();
+    let image = Image::new(Size::wh(2, 2), vec![Some(4), None, Some(8), Some(12)]);
+    let mut buffer = vec![0; (SCREEN_WIDTH * SCREEN_HEIGHT) as usize];
+
+    image.draw_scaled((10, 10), 2.0, Clip::SCREEN, &mut buffer, &pal);
+
+    assert_eq!(pixel(&buffer, 11, 11), palette_color(&pal, 4));
+    assert_eq!(pixel(&buffer, 12, 11), 0, "transparent");
+    assert_eq!(pixel(&buffer, 10, 13), palette_color(&pal, 8));
+    assert_eq!(pixel(&buffer, 13, 13), palette_color(&pal, 12));
+    assert_eq!(pixel(&buffer, 14, 13), 0);
+
+    let mut buffer = vec![0; (SCREEN_WIDTH * SCREEN_HEIGHT) as usize];
+    let clip = Clip {
+        bottom: 12,
+        ..Clip::SCREEN
+    };
+
+    // partly off the screen to the left, and cut at the bottom
+    image.draw_scaled((-2, 10), 2.0, clip, &mut buffer, &pal);
+
+    assert_eq!(pixel(&buffer, 0, 11), 0);
+    assert_eq!(pixel(&buffer, 0, 12), 0);
+    assert!(buffer.iter().all(|x| *x == 0));
+
+    image.draw_scaled((-1, 8), 2.0, clip, &mut buffer, &pal);
+
+    assert_eq!(pixel(&buffer, 0, 8), palette_color(&pal, 4));
+    assert_eq!(pixel(&buffer, 0, 10), palette_color(&pal, 8));
+    assert_eq!(pixel(&buffer, 0, 12), 0);
+}
+
+#[test]
+fn nearer_billboards_cover_farther_ones() {
+    let pal = palette();
+    let near = Image::new(Size::wh(1, 1), vec![Some(20)]);
+    let far = Image::new(Size::wh(1, 1), vec![Some(40)]);
+
+    let billboard = |image, depth| Billboard {
+        image,
+        x: 50.0,
+        y: 50.0,
+        scale: 10.0,
+        depth,
+        clip: 200.0,
+        alpha: 1.0,
+        palette: None,
+    };
+
+    let mut buffer = vec![0; (SCREEN_WIDTH * SCREEN_HEIGHT) as usize];
+    let mut billboards = [billboard(&near, 1.0), billboard(&far, 2.0)];
+
+    draw_billboards(&mut billboards, &Viewport::FULL, &mut buffer, &pal);
+
+    assert_eq!(pixel(&buffer, 50, 45), palette_color(&pal, 20));
+
+    // a see-through one in front mixes with the one behind
+    let mut billboards = [
+        Billboard {
+            alpha: 0.5,
+            ..billboard(&far, 1.0)
+        },
+        billboard(&near, 2.0),
+    ];
+
+    draw_billboards(&mut billboards, &Viewport::FULL, &mut buffer, &pal);
+
+    assert_eq!(
+        pixel(&buffer, 50, 45),
+        blend(palette_color(&pal, 20), palette_color(&pal, 40), 0.5)
+    );
+}
+
+#[test]
+fn players_car_is_in_front_at_the_bottom_of_its_view() {
+    let pal = palette();
+    let car = Image::new(Size::wh(2, 1), vec![Some(20); 2]);
+    let other = Image::new(Size::wh(1, 1), vec![Some(40)]);
+    let view = Viewport::split(2)[1];
+
+    let mut billboards = [
+        Billboard::player(&car, &view),
+        Billboard {
+            image: &other,
+            x: view.width as f32 / 2.0,
+            y: view.height as f32 - 10.0,
+            scale: 40.0,
+            depth: 1.0,
+            clip: view.height as f32,
+            alpha: 1.0,
+            palette: None,
+        },
+    ];
+
+    let mut buffer = vec![0; (SCREEN_WIDTH * SCREEN_HEIGHT) as usize];
+
+    draw_billboards(&mut billboards, &view, &mut buffer, &pal);
+
+    let bottom = view.y + view.height - 1;
+
+    assert_eq!(
+        pixel(&buffer, SCREEN_WIDTH / 2, bottom - 10),
+        palette_color(&pal, 20)
+    );
+    assert_eq!(pixel(&buffer, SCREEN_WIDTH / 2, bottom), 0);
+    assert_eq!(pixel(&buffer, SCREEN_WIDTH / 2, view.y - 1), 0);
+}
+
+#[test]
+fn objects_are_placed_by_the_track() {
+    let track = Track {
+        scatter: MAX_VALUE,
+        obstacles: MAX_VALUE,
+        ..Track::default()
+    };
+
+    let placements = place(&track, 1000, 5, 3);
+
+    assert_eq!(placements, place(&track, 1000, 5, 3));
+    assert!(placements
+        .iter()
+        .all(|x| x.segment >= 10 && x.segment < 1000));
+    assert!(placements
+        .iter()
+        .all(|x| x.obstacle == (x.offset.abs() < 1.0)));
+    assert!(placements.iter().any(|x| x.obstacle));
+    assert!(placements
+        .iter()
+        .all(|x| x.image < if x.obstacle { 3 } else { 5 }));
+
+    assert!(place(&Track::default(), 1000, 5, 3).is_empty());
+}
@@ -0,0 +1,77 @@
+use lotus3::game::{
+    options::{Course, Race},
+    scores::{course_key, Kind, Scores, TABLE_SIZE},
+};
+
+#[test]
+fn tables_keep_the_best_entries_in_order() {
+    let mut scores = Scores::default();
+
+    for (name, value) in [
+        ("A", 30),
+        ("B", 50),
+        ("C", 10),
+        ("D", 40),
+        ("E", 20),
+        ("F", 5),
+    ] {
+        scores.submit("T1", Kind::Score, name, value);
+        scores.submit("T1", Kind::Lap, name, value);
+    }
+
+    let names = |kind| {
+        scores
+            .table("T1", kind)
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(Kind::Score), ["B", "D", "A", "E", "C"]);
+    assert_eq!(names(Kind::Lap), ["F", "C", "E", "A", "D"]);
+    assert!(scores.table("T2", Kind::Score).is_empty());
+
+    // ties don't push the older entries down, and the last place is full
+    assert_eq!(scores.submit("T1", Kind::Score, "G", 40), Some(2));
+    assert_eq!(scores.submit("T1", Kind::Score, "H", 1), None);
+    assert_eq!(scores.table("T1", Kind::Score).len(), TABLE_SIZE);
+}
+
+#[test]
+fn scores_survive_a_round_trip() {
+    let mut scores = Scores::default();
+    let custom = course_key(Course::Unknown, Race::Competition, "XKXCJGFJH-33");
+    let t2 = course_key(Course::T2, Race::TimeLimit, "");
+
+    scores.submit(&t2, Kind::Score, "PLAYER 1", 25);
+    scores.submit(&custom, Kind::Lap, "NIGEL", 4321);
+
+    let (parsed, warnings) = Scores::parse(&scores.to_string());
+
+    assert_eq!(parsed, scores);
+    assert!(warnings.is_empty());
+    assert_eq!(parsed.table(&t2, Kind::Score)[0].value, 25);
+    assert_eq!(parsed.table(&custom, Kind::Lap)[0].name, "NIGEL");
+
+    // the race modes score differently, so they don't share the tables
+    assert_ne!(course_key(Course::T2, Race::Competition, ""), t2);
+    assert!(parsed
+        .table(&course_key(Course::T2, Race::Competition, ""), Kind::Score)
+        .is_empty());
+}
+
+#[test]
+fn bad_lines_are_skipped() {
+    let text = "# comment\nversion=1\nT1|lap|100|A\nT1|best|100|B\nT1|score|many|C\nnonsense\n";
+    let (scores, warnings) = Scores::parse(text);
+
+    assert_eq!(scores.table("T1", Kind::Lap).len(), 1);
+    assert!(scores.table("T1", Kind::Score).is_empty());
+    assert_eq!(warnings.len(), 3);
+    assert!(warnings[0].starts_with("line 4"));
+
+    let (_, warnings) = Scores::parse("version=2\nversion=x\n");
+
+    assert_eq!(warnings.len(), 2, "{warnings:?}");
+    assert!(warnings[0].starts_with("version 2 is newer"));
+}
@@ -0,0 +1,122 @@
+use anyhow::Result;
+use std::{env, fs, sync::Mutex, task::Poll, time::Duration};
+
+use lotus3::{
+    data::Archive,
+    engine::{GameEngine, State},
+    game::{options::Config, show_lotus_logo},
+    screen::{fade_out, screen},
+    task::{Clock, Executor, TICK, TICK_RATE},
+};
+
+const BLACK: u32 = 0xFF000000;
+const WHITE: u32 = 0xFFFFFFFF;
+
+/// The white of the palette, which has 6 bits a component.
+const PALETTE_WHITE: u32 = 0xFFFCFCFC;
+
+/// The screen is shared by every test of the file.
+static SCREEN: Mutex<()> = Mutex::new(());
+
+#[test]
+fn fade_out_follows_the_executor_clock() {
+    let _screen = SCREEN.lock().unwrap();
+
+    screen().fill(WHITE);
+
+    let executor = Executor::with_clock(Clock::manual());
+    let mut task = executor.spawn(fade_out());
+
+    executor.run();
+    assert_eq!(screen()[0], WHITE);
+
+    executor.clock().advance(Duration::from_millis(170));
+    (0..2).for_each(|_| executor.run());
+    assert!(screen().iter().all(|x| *x != WHITE && *x != BLACK));
+
+    executor.clock().advance(Duration::from_millis(2000));
+    (0..2).for_each(|_| executor.run());
+    assert_eq!(task.try_join(), Some(()));
+    assert!(screen().iter().all(|x| *x == BLACK));
+}
+
+/// Packs the runs of bytes the way the items of the archive are, see `data::zip`.
+fn pack(runs: &[(u16, u8)]) -> Vec<u8> {
+    // a single code in the table, an escape byte which never comes
+    let mut packed = vec![1, 0xAA, 0xAB, 0, 0];
+
+    for (len, value) in runs {
+        let [lo, hi] = len.to_le_bytes();
+        packed.extend([0xC0 | hi, lo, *value]);
+    }
+
+    packed.push(0);
+    packed
+}
+
+/// An archive of the white logo, in sectors of 512 bytes.
+fn logo_archive() -> Archive {
+    // a full screen of the first entry, then a white palette
+    let packed = pack(&[
+        (0x3F00, 0),
+        (0x3F00, 0),
+        (0x3F00, 0),
+        (0x3D00, 0),
+        (0x300, 63),
+    ]);
+    let sectors = packed.len().div_ceil(512) as u16;
+
+    let mut data = vec![0; 0xC];
+    data.extend(b"Q18\0\0\0\0\0");
+    data.extend(1u16.to_le_bytes());
+    data.extend(b"END\0\0\0\0\0");
+    data.extend((1 + sectors).to_le_bytes());
+    data.resize(512, 0);
+    data.extend(&packed);
+    data.resize((1 + sectors as usize) * 512, 0);
+
+    let path = env::temp_dir().join(format!("lotus3-{}-logo.dat", std::process::id()));
+    fs::write(&path, data).unwrap();
+
+    Archive::open(&path).unwrap()
+}
+
+async fn logo(mut state: State) -> Result<()> {
+    show_lotus_logo(&mut state).await
+}
+
+/// Steps the engine tick by tick for the time or until the game is over.
+fn run_for(engine: &mut GameEngine, ms: u32) -> Poll<()> {
+    for _ in 0..ms * TICK_RATE / 1000 {
+        engine.clock().advance(TICK);
+
+        if engine.step().unwrap().is_ready() {
+            return Poll::Ready(());
+        }
+    }
+
+    Poll::Pending
+}
+
+#[test]
+fn lotus_logo_fades_in_and_out_with_the_executor_clock() {
+    let _screen = SCREEN.lock().unwrap();
+
+    let mut engine = GameEngine::new(logo_archive(), Config::new(), Clock::manual(), logo).unwrap();
+
+    assert_eq!(engine.step().unwrap(), Poll::Pending);
+    assert!(screen().iter().all(|x| *x == BLACK));
+
+    assert_eq!(run_for(&mut engine, 170), Poll::Pending);
+    assert!(screen().iter().all(|x| *x != PALETTE_WHITE && *x != BLACK));
+
+    // faded in, the logo stays up for a while
+    assert_eq!(run_for(&mut engine, 500), Poll::Pending);
+    assert!(screen().iter().all(|x| *x == PALETTE_WHITE));
+
+    assert_eq!(run_for(&mut engine, 1000), Poll::Pending);
+    assert!(screen().iter().all(|x| *x == PALETTE_WHITE));
+
+    assert_eq!(run_for(&mut engine, 1500), Poll::Ready(()));
+    assert!(screen().iter().all(|x| *x == BLACK));
+}
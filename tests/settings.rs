@@ -0,0 +1,54 @@
+use std::{env, fs};
+
+use lotus3::game::{
+    options::{Config, Course, Transmission},
+    settings::{self, SETTINGS_VERSION},
+};
+
+#[test]
+fn settings_round_trip_through_a_file() {
+    let path = env::temp_dir()
+        .join(format!("lotus3-{}", std::process::id()))
+        .join("settings.cfg");
+
+    assert_eq!(settings::load(&path).unwrap(), Config::new());
+
+    let mut cfg = Config::new();
+    cfg.p2_name = "BOB 2".to_string();
+    cfg.p1_trans = Transmission::Manual;
+    cfg.course = Course::T3;
+    cfg.players_num = 2;
+    cfg.code = "         -38".to_string();
+
+    settings::save(&cfg, &path).unwrap();
+
+    let text = fs::read_to_string(&path).unwrap();
+    assert!(text.contains(&format!("version={SETTINGS_VERSION}\n")));
+    assert_eq!(settings::load(&path).unwrap(), cfg);
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn bad_lines_keep_their_defaults() {
+    let text = "version=1\ncourse=t2\nrace=sprint\nplayers_num=7\nnonsense\np1_brake=F13\ncode=XKXCJGFJH-16\n";
+    let (cfg, warnings) = settings::parse(text);
+
+    assert_eq!(cfg.course, Course::T2);
+    assert_eq!(cfg.race, Config::new().race);
+    assert_eq!(cfg.players_num, 1);
+    assert_eq!(cfg.controls, Config::new().controls);
+    assert_eq!(cfg.code, Config::new().code);
+    assert_eq!(warnings.len(), 5, "{warnings:?}");
+    assert!(warnings.iter().any(|x| x.starts_with("line 3:")));
+    assert!(warnings.iter().any(|x| x.starts_with("line 7:")));
+}
+
+#[test]
+fn unversioned_files_are_read_as_current() {
+    let (cfg, warnings) = settings::parse("p1_name=ANN\ncourse=circular\n");
+
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(cfg.p1_name, "ANN");
+    assert_eq!(cfg.course, Course::Circular);
+}
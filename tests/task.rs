@@ -0,0 +1,205 @@
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use lotus3::task::{
+    join, now, select, sleep, spawn, tick, yield_now, CancelReason, CancellationToken, Cancelled,
+    Clock, Either, Executor, TICK,
+};
+
+async fn count(n: u32) -> u32 {
+    for _ in 0..n {
+        yield_now().await;
+    }
+
+    n
+}
+
+#[test]
+fn executor_polls_woken_tasks_once_per_run() {
+    let executor = Executor::new();
+    let mut task = executor.spawn(count(3));
+
+    for _ in 0..3 {
+        executor.run();
+        assert!(!task.is_finished());
+    }
+
+    executor.run();
+    assert_eq!(task.try_join(), Some(3));
+    assert!(executor.is_empty());
+}
+
+#[test]
+fn join_waits_for_both_futures() {
+    let executor = Executor::new();
+    let mut task = executor.spawn(join(count(1), count(4)));
+
+    (0..5).for_each(|_| executor.run());
+
+    assert_eq!(task.try_join(), Some((1, 4)));
+}
+
+#[test]
+fn select_resolves_with_the_first_future() {
+    let executor = Executor::new();
+    let mut task = executor.spawn(async {
+        match select(count(5), count(2)).await {
+            Either::Left(n) | Either::Right(n) => n,
+        }
+    });
+
+    (0..3).for_each(|_| executor.run());
+
+    assert_eq!(task.try_join(), Some(2));
+}
+
+#[test]
+fn aborted_task_is_not_polled_anymore() {
+    let polls = Rc::new(Cell::new(0));
+
+    let executor = Executor::new();
+    let mut task = executor.spawn({
+        let polls = Rc::clone(&polls);
+
+        async move {
+            let child = spawn(async move {
+                loop {
+                    polls.set(polls.get() + 1);
+                    yield_now().await;
+                }
+            });
+
+            count(2).await;
+            child.abort();
+            child.await
+        }
+    });
+
+    (0..5).for_each(|_| executor.run());
+
+    assert_eq!(task.try_join(), Some(None));
+    assert_eq!(polls.get(), 2);
+    assert!(executor.is_empty());
+}
+
+#[test]
+fn sleeping_task_is_woken_at_its_deadline() {
+    let executor = Executor::new();
+    let mut task = executor.spawn(sleep(20));
+
+    executor.run();
+
+    let deadline = executor.next_wakeup().unwrap();
+    assert!(deadline > Instant::now());
+
+    executor.run();
+    assert!(!task.is_finished());
+
+    std::thread::sleep(deadline - Instant::now() + Duration::from_millis(1));
+
+    executor.run();
+    assert_eq!(task.try_join(), Some(()));
+    assert_eq!(executor.next_wakeup(), None);
+}
+
+#[test]
+fn manual_clock_moves_only_when_advanced() {
+    let executor = Executor::with_clock(Clock::manual());
+    let mut task = executor.spawn(async {
+        sleep(2000).await;
+        now()
+    });
+
+    executor.run();
+    assert_eq!(executor.next_wakeup(), None);
+
+    executor.clock().advance(Duration::from_millis(1999));
+    executor.run();
+    assert!(!task.is_finished());
+
+    executor.clock().advance(Duration::from_millis(1));
+    executor.run();
+    assert_eq!(task.try_join(), Some(Duration::from_millis(2000)));
+}
+
+#[test]
+fn fixed_step_clock_moves_on_every_run() {
+    let executor = Executor::with_clock(Clock::fixed_step(Duration::from_millis(10)));
+    let mut task = executor.spawn(sleep(30));
+
+    (0..3).for_each(|_| executor.run());
+    assert!(!task.is_finished());
+
+    executor.run();
+    assert_eq!(task.try_join(), Some(()));
+    assert_eq!(executor.clock().now(), Duration::from_millis(40));
+}
+
+#[test]
+fn cancelled_token_stops_a_sleeping_future() {
+    let token = CancellationToken::new();
+    let child = token.child();
+
+    let executor = Executor::with_clock(Clock::manual());
+    let mut task = executor.spawn(child.run(async {
+        sleep(1000).await;
+        1
+    }));
+
+    executor.run();
+    token.cancel(CancelReason::Skipped);
+    token.cancel(CancelReason::Shutdown);
+
+    executor.run();
+    assert_eq!(task.try_join(), Some(Err(Cancelled(CancelReason::Skipped))));
+    assert_eq!(child.reason(), Some(CancelReason::Skipped));
+}
+
+#[test]
+fn cancelling_a_child_leaves_the_parent_running() {
+    let token = CancellationToken::new();
+    let child = token.child();
+
+    let executor = Executor::new();
+    let mut task = executor.spawn(token.run(async move {
+        child.cancel(CancelReason::Skipped);
+        child.cancelled().await
+    }));
+
+    executor.run();
+    assert_eq!(task.try_join(), Some(Ok(CancelReason::Skipped)));
+    assert!(!token.is_cancelled());
+}
+
+#[test]
+fn ticks_are_issued_at_a_fixed_rate() {
+    let executor = Executor::with_clock(Clock::manual());
+    let mut task = executor.spawn(async {
+        let mut ticks = Vec::new();
+
+        while ticks.len() < 8 {
+            ticks.push(tick().await);
+        }
+
+        ticks
+    });
+
+    executor.run();
+    assert_eq!(executor.stats().ticks, 0);
+
+    executor.clock().advance(TICK * 3 + TICK / 2);
+    executor.run();
+    assert_eq!(executor.stats().ticks, 3);
+    assert!((executor.stats().alpha - 0.5).abs() < 1e-6);
+
+    // a stall is caught up partially
+    executor.clock().advance(TICK * 10);
+    executor.run();
+    assert_eq!(executor.stats().ticks, 8);
+    assert_eq!(executor.stats().dropped_ticks, 5);
+
+    assert_eq!(task.try_join(), Some((1..=8).collect()));
+}